@@ -1,3 +1,6 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -8,6 +11,21 @@ pub enum CorridorKind {
     Thermal,
     Acoustic,
     Chemical,
+    /// Ambient CO2, as a proxy for hive ventilation.
+    Co2,
+}
+
+impl CorridorKind {
+    /// Unit every `CorridorEnvelope` of this kind is declared in.
+    pub fn unit(self) -> &'static str {
+        match self {
+            CorridorKind::EMF => "V/m",
+            CorridorKind::Thermal => "C",
+            CorridorKind::Acoustic => "dB",
+            CorridorKind::Chemical => "mg/m3",
+            CorridorKind::Co2 => "ppm",
+        }
+    }
 }
 
 /// Envelope parameters for one corridor at a given point in space-time.
@@ -18,6 +36,25 @@ pub struct CorridorEnvelope {
     pub l_max: f64,
     /// Lower bound L_min (optional; often 0.0 for safety).
     pub l_min: f64,
+    /// Unit the bounds are declared in; empty means `CorridorKind::unit`.
+    #[serde(default)]
+    pub unit: String,
+}
+
+impl CorridorEnvelope {
+    /// Envelope in the kind's default unit.
+    pub fn new(kind: CorridorKind, l_min: f64, l_max: f64) -> Self {
+        Self { kind, l_max, l_min, unit: kind.unit().into() }
+    }
+
+    /// Unit the bounds are declared in, falling back to `CorridorKind::unit`.
+    pub fn unit(&self) -> &str {
+        if self.unit.is_empty() {
+            self.kind.unit()
+        } else {
+            &self.unit
+        }
+    }
 }
 
 /// Bee-relevant spatial context for a node.
@@ -29,14 +66,154 @@ pub struct BeeContext {
     pub in_hive_exclusion: bool,
     /// Vertical distance to dominant bee flight band (m).
     pub dz_to_bee_band: f64,
+    /// Per-corridor overrides of `bee_sensitivity`; kinds not listed use the scalar.
+    #[serde(default)]
+    pub sensitivity_by_kind: HashMap<CorridorKind, f64>,
+}
+
+/// Geometry of one hive-exclusion zone, in local metric coordinates (x, y, z).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ExclusionShape {
+    Sphere { center: [f64; 3], radius: f64 },
+    /// Vertical cylinder around a hive stand, spanning z_min..=z_max.
+    Cylinder { center_xy: [f64; 2], radius: f64, z_min: f64, z_max: f64 },
+    /// Axis-aligned box, e.g. a row of hives or a buffer strip.
+    Box { min: [f64; 3], max: [f64; 3] },
+}
+
+impl ExclusionShape {
+    pub fn contains(&self, point: [f64; 3]) -> bool {
+        match self {
+            ExclusionShape::Sphere { center, radius } => {
+                let d2: f64 = (0..3).map(|i| (point[i] - center[i]).powi(2)).sum();
+                d2 <= radius * radius
+            }
+            ExclusionShape::Cylinder { center_xy, radius, z_min, z_max } => {
+                let dx = point[0] - center_xy[0];
+                let dy = point[1] - center_xy[1];
+                dx * dx + dy * dy <= radius * radius && point[2] >= *z_min && point[2] <= *z_max
+            }
+            ExclusionShape::Box { min, max } => (0..3).all(|i| point[i] >= min[i] && point[i] <= max[i]),
+        }
+    }
+}
+
+/// Set of hive-exclusion zones for an apiary; a point is excluded if any zone contains it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HiveExclusionMap {
+    pub zones: Vec<ExclusionShape>,
+}
+
+impl HiveExclusionMap {
+    pub fn contains(&self, point: [f64; 3]) -> bool {
+        self.zones.iter().any(|z| z.contains(point))
+    }
+}
+
+/// Point in the bee-rights parameter space, axes ordered as `POLYTOPE_AXES`.
+pub type ParameterVector = [f64; 4];
+
+/// Half-space `a · x + b <= 0` given as the normal `a` and offset `b`.
+pub type PolytopeConstraint = (ParameterVector, f64);
+
+/// Axis labels of a `ParameterVector`, in array order.
+pub const POLYTOPE_AXES: [&str; 4] = ["distance_from_hive_m", "o3_ppb", "emf_vpm", "duty_cycle"];
+
+/// Convex bee-rights polytope: the intersection of its constraint half-spaces.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BeerightsPolytope {
+    pub constraints: Vec<PolytopeConstraint>,
+}
+
+/// `ParameterVector` with each axis tagged by name, see `POLYTOPE_AXES`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NamedAxes {
+    pub distance_from_hive_m: f64,
+    pub o3_ppb: f64,
+    pub emf_vpm: f64,
+    pub duty_cycle: f64,
+}
+
+impl From<ParameterVector> for NamedAxes {
+    fn from(v: ParameterVector) -> Self {
+        Self { distance_from_hive_m: v[0], o3_ppb: v[1], emf_vpm: v[2], duty_cycle: v[3] }
+    }
+}
+
+impl From<NamedAxes> for ParameterVector {
+    fn from(n: NamedAxes) -> Self {
+        [n.distance_from_hive_m, n.o3_ppb, n.emf_vpm, n.duty_cycle]
+    }
+}
+
+/// Self-describing constraint `normal · x + offset <= 0`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NamedConstraint {
+    pub normal: NamedAxes,
+    pub offset: f64,
+}
+
+/// Storage form of a `BeerightsPolytope` with named axes instead of bare arrays.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NamedPolytope {
+    pub constraints: Vec<NamedConstraint>,
+}
+
+impl BeerightsPolytope {
+    pub fn to_named(&self) -> NamedPolytope {
+        NamedPolytope {
+            constraints: self
+                .constraints
+                .iter()
+                .map(|(a, b)| NamedConstraint { normal: (*a).into(), offset: *b })
+                .collect(),
+        }
+    }
+
+    pub fn from_named(named: &NamedPolytope) -> Self {
+        Self {
+            constraints: named.constraints.iter().map(|c| (c.normal.into(), c.offset)).collect(),
+        }
+    }
+
+    /// True if `x` satisfies every constraint.
+    pub fn contains(&self, x: &ParameterVector) -> bool {
+        self.constraints
+            .iter()
+            .all(|(a, b)| a.iter().zip(x).map(|(ai, xi)| ai * xi).sum::<f64>() + b <= 0.0)
+    }
 }
 
 /// Local predicted levels for each corridor produced by hardware or a local model.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PredictedLevels {
     pub kind: CorridorKind,
-    /// Predicted level L_k at this node (already aggregated over frequency band if needed).
+    /// Predicted level L_k at this node, aggregated over frequency (see `PredictedSpectrum`).
     pub level: f64,
+    /// Unit of `level`; checked against `CorridorEnvelope::unit` in strict mode.
+    #[serde(default)]
+    pub unit: String,
+}
+
+/// Frequency-resolved prediction for one corridor, e.g. EMF field strength per RF
+/// band, since bee sensitivity depends on frequency.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PredictedSpectrum {
+    pub kind: CorridorKind,
+    /// (freq_ghz, level) per frequency bin.
+    pub bins: Vec<(f64, f64)>,
+}
+
+/// Envelope over one frequency band, like `beecorridor_core::RFEnvelope`:
+/// applies to spectrum bins of `kind` with `f_ghz_min <= freq <= f_ghz_max`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpectralEnvelope {
+    pub kind: CorridorKind,
+    pub f_ghz_min: f64,
+    pub f_ghz_max: f64,
+    pub l_min: f64,
+    pub l_max: f64,
 }
 
 /// Node state exposed to the Bee Safety Kernel.
@@ -55,8 +232,36 @@ pub struct NodeState {
     pub cybo_weight: f64,
     /// Bee context at node location.
     pub bee_ctx: BeeContext,
+    /// Essential nodes (e.g. critical air cleaners) keep a minimum duty outside hive exclusion.
+    #[serde(default)]
+    pub essential: bool,
     /// Predicted local levels for all corridors at the proposed duty_cycle.
     pub predicted_levels: Vec<PredictedLevels>,
+    /// Frequency-resolved predictions, checked bin by bin against `spectral_envelopes`.
+    #[serde(default)]
+    pub predicted_spectra: Vec<PredictedSpectrum>,
+}
+
+/// Shape of the corridor penalty as a function of envelope excursion d.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PhiShape {
+    /// d: proportional, predictable control response.
+    Linear,
+    /// d^2: weights large breaches heavily.
+    #[default]
+    Quadratic,
+    /// d^3: near-free small excursions, steep large ones.
+    Cubic,
+}
+
+impl PhiShape {
+    pub fn apply(self, excursion: f64) -> f64 {
+        match self {
+            PhiShape::Linear => excursion,
+            PhiShape::Quadratic => excursion * excursion,
+            PhiShape::Cubic => excursion * excursion * excursion,
+        }
+    }
 }
 
 /// Scalar parameters governing corridor enforcement and duty-cycle update.
@@ -72,29 +277,138 @@ pub struct KernelParams {
     pub phi_ref: f64,
     pub alpha_z: f64,
     pub beta_s: f64,
+    /// Duty-cycle floor for essential nodes that are not in hive exclusion.
+    #[serde(default)]
+    pub min_essential_duty: f64,
+    /// Penalty shape applied to each envelope excursion in `compute_phi`.
+    #[serde(default)]
+    pub phi_shape: PhiShape,
+    /// Bee factor for any excursion inside a hive exclusion zone.
+    #[serde(default = "default_hive_exclusion_penalty")]
+    pub hive_exclusion_penalty: f64,
+    /// Largest change in duty cycle per step in `evaluate_node_rate_limited`.
+    #[serde(default = "default_max_duty_delta")]
+    pub max_duty_delta: f64,
+}
+
+/// Default `hive_exclusion_penalty`: large enough to dominate any other corridor.
+pub const DEFAULT_HIVE_EXCLUSION_PENALTY: f64 = 1e6;
+
+fn default_hive_exclusion_penalty() -> f64 {
+    DEFAULT_HIVE_EXCLUSION_PENALTY
 }
 
+/// A step can cover the whole [0,1] range, i.e. no rate limit.
+fn default_max_duty_delta() -> f64 {
+    1.0
+}
+
+/// Three-way outcome of a kernel evaluation.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum KernelVerdict {
+    /// No corridor penalty: run at `safe_duty_cycle`.
+    Permit,
+    /// Nonzero penalty below `phi_ref`, or any penalty on an essential node held
+    /// at its duty floor: run at no more than the carried duty cycle.
+    Derate(f64),
+    /// Penalty at or above `phi_ref` (unless an essential floor applies), hive
+    /// exclusion, or no duty left. `safe_duty_cycle` is always 0.0.
+    #[default]
+    Deny,
+}
+
+/// Schema version stamped on every `KernelDecision` this kernel produces.
+/// Payloads written before versioning deserialize as version 0.
+pub const CURRENT_SCHEMA_VERSION: u32 = 1;
+
 /// Result of a kernel evaluation.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct KernelDecision {
+    #[serde(default)]
+    pub schema_version: u32,
     pub node_id: String,
     /// Updated, bee-safe duty cycle in [0,1].
     pub safe_duty_cycle: f64,
-    /// True if emission is permitted under current envelopes.
+    /// True if emission is permitted under current envelopes, i.e. `verdict` is `Permit`.
     pub permitted: bool,
+    #[serde(default)]
+    pub verdict: KernelVerdict,
     /// Sum of corridor penalties Φ_i(u).
     pub phi_penalty: f64,
+    /// `phi_penalty` split by corridor kind, to see which emitter to throttle.
+    #[serde(default)]
+    pub phi_by_kind: HashMap<CorridorKind, f64>,
     /// Bee-weighted eco-impact score S_i^{bee} in [0,1].
     pub eco_impact_bee: f64,
 }
 
 /// Errors raised by the kernel.
-#[derive(Debug, Error)]
+#[derive(Debug, PartialEq, Error)]
 pub enum KernelError {
     #[error("No corridor envelopes provided")]
     NoEnvelopes,
     #[error("Duty cycle must be in [0,1], got {0}")]
     InvalidDutyCycle(f64),
+    #[error("Got {decisions} decisions for {nodes} nodes")]
+    FleetSizeMismatch { decisions: usize, nodes: usize },
+    #[error("Decision for node {decision} paired with node {node}")]
+    NodeMismatch { decision: String, node: String },
+    #[error("More than one envelope for corridor {0:?}")]
+    DuplicateEnvelope(CorridorKind),
+    #[error("{kind:?} level given in {got:?}, envelope expects {expected}")]
+    UnitMismatch { kind: CorridorKind, expected: String, got: String },
+    #[error("No predicted level for corridor {0:?}")]
+    MissingPrediction(CorridorKind),
+    #[error("{kind:?} spectral envelope inverted: {f_ghz_min}..{f_ghz_max} GHz, {l_min}..{l_max}")]
+    InvalidSpectralEnvelope {
+        kind: CorridorKind,
+        f_ghz_min: f64,
+        f_ghz_max: f64,
+        l_min: f64,
+        l_max: f64,
+    },
+    #[error("Decision schema version {found} is newer than supported version {supported}")]
+    UnsupportedSchema { found: u32, supported: u32 },
+    #[error("Invalid decision JSON: {0}")]
+    InvalidJson(String),
+}
+
+impl KernelDecision {
+    /// Read a stored decision. Older versions load with their missing fields
+    /// defaulted; a version newer than `CURRENT_SCHEMA_VERSION` may carry fields
+    /// this kernel would silently drop, so it is rejected.
+    pub fn from_json(s: &str) -> Result<Self, KernelError> {
+        let decision: Self =
+            serde_json::from_str(s).map_err(|e| KernelError::InvalidJson(e.to_string()))?;
+        if decision.schema_version > CURRENT_SCHEMA_VERSION {
+            return Err(KernelError::UnsupportedSchema {
+                found: decision.schema_version,
+                supported: CURRENT_SCHEMA_VERSION,
+            });
+        }
+        Ok(decision)
+    }
+}
+
+/// Terms of the duty-cycle update (Eq. 6) behind one `evaluate_node` decision.
+/// All zero for a hive exclusion shutdown, where the update never runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct DutyUpdateTrace {
+    /// `eta_mass * mass_removed_kg / m_ref`.
+    pub mass_term: f64,
+    /// `eta_karma * nano_karma_bytes / k_ref`.
+    pub karma_term: f64,
+    /// `eta_geo` times the bee-refined geospatial weight.
+    pub geo_term: f64,
+    /// `eta_power * power_cost`, subtracted.
+    pub power_penalty: f64,
+    /// `eta_bee * phi / phi_ref`, subtracted.
+    pub bee_penalty: f64,
+    /// Duty cycle plus the terms above, before projection.
+    pub raw_u: f64,
+    /// Final duty cycle after projection to [0,1], the essential floor and the
+    /// verdict (0.0 on Deny).
+    pub projected_u: f64,
 }
 
 /// Core Bee Safety Kernel object.
@@ -102,6 +416,10 @@ pub struct BeeSafetyKernel {
     /// Corridor envelopes indexed by CorridorKind.
     pub envelopes: Vec<CorridorEnvelope>,
     pub params: KernelParams,
+    /// Reject predicted levels whose unit differs from their envelope's.
+    pub strict_units: bool,
+    /// Frequency-keyed envelopes for `NodeState::predicted_spectra`.
+    pub spectral_envelopes: Vec<SpectralEnvelope>,
 }
 
 impl BeeSafetyKernel {
@@ -109,30 +427,158 @@ impl BeeSafetyKernel {
         if envelopes.is_empty() {
             return Err(KernelError::NoEnvelopes);
         }
-        Ok(Self { envelopes, params })
+        // `envelope_for` takes the first match, so a second one would be silently ignored.
+        for (i, env) in envelopes.iter().enumerate() {
+            if envelopes[..i].iter().any(|e| e.kind == env.kind) {
+                return Err(KernelError::DuplicateEnvelope(env.kind));
+            }
+        }
+        Ok(Self { envelopes, params, strict_units: false, spectral_envelopes: Vec::new() })
     }
 
-    fn envelope_for(&self, kind: CorridorKind) -> Option<&CorridorEnvelope> {
-        self.envelopes.iter().find(|e| e.kind == kind)
+    /// Enforce frequency-keyed envelopes on nodes' predicted spectra. Each band
+    /// needs `f_ghz_min <= f_ghz_max` and `l_min <= l_max`.
+    pub fn with_spectral_envelopes(
+        mut self,
+        envelopes: Vec<SpectralEnvelope>,
+    ) -> Result<Self, KernelError> {
+        // Negated so NaN bounds are rejected too.
+        let bad = envelopes.iter().find(|e| !(e.f_ghz_min <= e.f_ghz_max && e.l_min <= e.l_max));
+        if let Some(e) = bad {
+            return Err(KernelError::InvalidSpectralEnvelope {
+                kind: e.kind,
+                f_ghz_min: e.f_ghz_min,
+                f_ghz_max: e.f_ghz_max,
+                l_min: e.l_min,
+                l_max: e.l_max,
+            });
+        }
+        self.spectral_envelopes = envelopes;
+        Ok(self)
     }
 
-    /// Compute corridor penalty Φ_i(u) as in Eq. (5) using local predicted levels.
-    fn compute_phi(&self, node: &NodeState) -> f64 {
-        let mut phi = 0.0;
+    /// Enable unit checking of predicted levels in `evaluate_node`.
+    pub fn with_strict_units(mut self) -> Self {
+        self.strict_units = true;
+        self
+    }
+
+    /// In strict mode, every predicted level with an envelope must carry that
+    /// envelope's unit (`CorridorEnvelope::unit`).
+    fn check_units(&self, node: &NodeState) -> Result<(), KernelError> {
+        if !self.strict_units {
+            return Ok(());
+        }
         for pl in &node.predicted_levels {
             if let Some(env) = self.envelope_for(pl.kind) {
+                if pl.unit != env.unit() {
+                    return Err(KernelError::UnitMismatch {
+                        kind: pl.kind,
+                        expected: env.unit().into(),
+                        got: pl.unit.clone(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// `compute_phi` only sees corridors the node predicts, so an omitted one
+    /// would go unenforced. A spectrum stands in for a scalar level only when
+    /// every one of its bins falls inside a spectral envelope of its kind, since
+    /// uncovered bins carry no penalty.
+    fn check_predictions(&self, node: &NodeState) -> Result<(), KernelError> {
+        for env in &self.envelopes {
+            let predicted = node.predicted_levels.iter().any(|pl| pl.kind == env.kind)
+                || node
+                    .predicted_spectra
+                    .iter()
+                    .any(|sp| sp.kind == env.kind && self.spectrum_covered(sp));
+            if !predicted {
+                return Err(KernelError::MissingPrediction(env.kind));
+            }
+        }
+        Ok(())
+    }
+
+    fn envelope_for(&self, kind: CorridorKind) -> Option<&CorridorEnvelope> {
+        self.envelopes.iter().find(|e| e.kind == kind)
+    }
+
+    /// Unweighted envelope excursion for one predicted level, shaped by `phi_shape`.
+    fn level_penalty(&self, pl: &PredictedLevels) -> f64 {
+        match self.envelope_for(pl.kind) {
+            Some(env) => {
                 let over = (pl.level - env.l_max).max(0.0);
                 let under = (env.l_min - pl.level).max(0.0);
-                phi += over * over + under * under;
+                let shape = self.params.phi_shape;
+                shape.apply(over) + shape.apply(under)
             }
+            None => 0.0,
         }
-        // Weight by bee sensitivity; hive exclusion makes any non-zero penalty very large.
-        let bee_factor = if node.bee_ctx.in_hive_exclusion {
-            1e6
+    }
+
+    /// Spectral envelope of the spectrum's kind whose band contains `f_ghz`.
+    fn spectral_envelope_for(&self, kind: CorridorKind, f_ghz: f64) -> Option<&SpectralEnvelope> {
+        self.spectral_envelopes
+            .iter()
+            .find(|e| e.kind == kind && f_ghz >= e.f_ghz_min && f_ghz <= e.f_ghz_max)
+    }
+
+    /// Non-empty spectrum with every bin inside some spectral envelope.
+    fn spectrum_covered(&self, sp: &PredictedSpectrum) -> bool {
+        let covered =
+            |&(f_ghz, _): &(f64, f64)| self.spectral_envelope_for(sp.kind, f_ghz).is_some();
+        !sp.bins.is_empty() && sp.bins.iter().all(covered)
+    }
+
+    /// Largest shaped excursion over a spectrum's bins, each checked against the
+    /// first spectral envelope of its kind whose band contains it. Bins outside
+    /// every band carry no penalty.
+    fn spectrum_penalty(&self, sp: &PredictedSpectrum) -> f64 {
+        let shape = self.params.phi_shape;
+        sp.bins
+            .iter()
+            .filter_map(|&(f_ghz, level)| {
+                let env = self.spectral_envelope_for(sp.kind, f_ghz)?;
+                let over = (level - env.l_max).max(0.0);
+                let under = (env.l_min - level).max(0.0);
+                Some(shape.apply(over) + shape.apply(under))
+            })
+            .fold(0.0, f64::max)
+    }
+
+    /// Weight by bee sensitivity for one corridor; hive exclusion makes any
+    /// non-zero penalty very large.
+    fn bee_factor(&self, node: &NodeState, kind: CorridorKind) -> f64 {
+        if node.bee_ctx.in_hive_exclusion {
+            self.params.hive_exclusion_penalty
         } else {
-            node.bee_ctx.bee_sensitivity.max(1.0)
-        };
-        phi * bee_factor
+            node.bee_ctx
+                .sensitivity_by_kind
+                .get(&kind)
+                .copied()
+                .unwrap_or(node.bee_ctx.bee_sensitivity)
+                .max(1.0)
+        }
+    }
+
+    /// Compute corridor penalty Φ_i(u) as in Eq. (5) using local predicted levels,
+    /// with its breakdown by corridor kind (one entry per predicted kind).
+    fn compute_phi(&self, node: &NodeState) -> (f64, HashMap<CorridorKind, f64>) {
+        let mut phi = 0.0;
+        let mut phi_by_kind: HashMap<CorridorKind, f64> = HashMap::new();
+        for pl in &node.predicted_levels {
+            let term = self.level_penalty(pl) * self.bee_factor(node, pl.kind);
+            phi += term;
+            *phi_by_kind.entry(pl.kind).or_insert(0.0) += term;
+        }
+        for sp in &node.predicted_spectra {
+            let term = self.spectrum_penalty(sp) * self.bee_factor(node, sp.kind);
+            phi += term;
+            *phi_by_kind.entry(sp.kind).or_insert(0.0) += term;
+        }
+        (phi, phi_by_kind)
     }
 
     /// Compute bee-refined geospatial weight w_i^{bee} from Eq. (7).
@@ -148,23 +594,89 @@ impl BeeSafetyKernel {
     /// Compute bee-normalized eco-impact S_i^{bee} from Eq. (8).
     fn compute_eco_impact_bee(&self, node: &NodeState, phi: f64) -> f64 {
         let p = &self.params;
-        let s_mass = (node.nano_karma_bytes / (self.params.k_ref + 1e-9)).min(2.0);
-        let s_pollutant = 1.0 - (s_mass - 1.0).abs(); // crude compression to [0,1]
+        let s_mass = (node.mass_removed_kg / (p.m_ref + 1e-12)).max(0.0);
+        // Saturating in [0,1): removing more mass never lowers the score.
+        let s_pollutant = s_mass / (1.0 + s_mass);
         let s_bee = 1.0 - (phi / (p.phi_ref + 1e-9)).min(1.0);
         let s = p.beta_s * s_pollutant + (1.0 - p.beta_s) * s_bee;
         s.clamp(0.0, 1.0)
     }
 
+    /// Marginal eco-impact per corridor: how much `eco_impact_bee` would rise if
+    /// that corridor's penalty were zeroed while the others stay in place.
+    pub fn eco_impact_sensitivity(&self, node: &NodeState) -> HashMap<CorridorKind, f64> {
+        let (phi, phi_by_kind) = self.compute_phi(node);
+        let base = self.compute_eco_impact_bee(node, phi);
+
+        phi_by_kind
+            .into_iter()
+            .map(|(kind, phi_k)| {
+                let without = self.compute_eco_impact_bee(node, (phi - phi_k).max(0.0));
+                (kind, without - base)
+            })
+            .collect()
+    }
+
+    /// Each predicted level normalized against its envelope,
+    /// `((level - l_min) / (l_max - l_min)).clamp(0, 1)`, in prediction order.
+    /// Levels without an envelope are skipped; a zero-width envelope maps to 1.0
+    /// above `l_min` and 0.0 otherwise.
+    pub fn normalized_risks(&self, node: &NodeState) -> Vec<(CorridorKind, f64)> {
+        node.predicted_levels
+            .iter()
+            .filter_map(|pl| {
+                let env = self.envelope_for(pl.kind)?;
+                let width = env.l_max - env.l_min;
+                let r = if width > 0.0 {
+                    (pl.level - env.l_min) / width
+                } else if pl.level > env.l_min {
+                    1.0
+                } else {
+                    0.0
+                };
+                Some((pl.kind, r.clamp(0.0, 1.0)))
+            })
+            .collect()
+    }
+
     /// Evaluate one node and return a bee-safe duty cycle and decision.
-    pub fn evaluate_node(&self, mut node: NodeState) -> Result<KernelDecision, KernelError> {
+    pub fn evaluate_node(&self, node: NodeState) -> Result<KernelDecision, KernelError> {
+        self.evaluate_node_with_trace(node).map(|(decision, _)| decision)
+    }
+
+    /// `evaluate_node` that also returns each term of the duty-cycle update, for
+    /// tuning the `eta_*` gains.
+    pub fn evaluate_node_with_trace(
+        &self,
+        node: NodeState,
+    ) -> Result<(KernelDecision, DutyUpdateTrace), KernelError> {
         if !(0.0..=1.0).contains(&node.duty_cycle) {
             return Err(KernelError::InvalidDutyCycle(node.duty_cycle));
         }
 
+        self.check_predictions(&node)?;
+        self.check_units(&node)?;
         let p = &self.params;
 
         // Corridor penalty Φ_i(u)
-        let phi = self.compute_phi(&node);
+        let (phi, phi_by_kind) = self.compute_phi(&node);
+
+        // Any emission inside a hive exclusion zone shuts the node off outright,
+        // without running the duty-cycle update on a penalty-scaled phi.
+        if Self::exclusion_shutdown(&node) {
+            let eco_impact_bee = self.compute_eco_impact_bee(&node, phi);
+            let decision = KernelDecision {
+                schema_version: CURRENT_SCHEMA_VERSION,
+                node_id: node.node_id,
+                safe_duty_cycle: 0.0,
+                permitted: false,
+                verdict: KernelVerdict::Deny,
+                phi_penalty: phi,
+                phi_by_kind,
+                eco_impact_bee,
+            };
+            return Ok((decision, DutyUpdateTrace::default()));
+        }
 
         // Bee-refined geospatial weight
         let w_bee = self.compute_bee_weight(&node);
@@ -174,30 +686,228 @@ impl BeeSafetyKernel {
         let k_norm = node.nano_karma_bytes / (p.k_ref + 1e-12);
 
         // Duty-cycle update (Eq. 6)
-        let mut u = node.duty_cycle
-            + p.eta_mass * m_norm
-            + p.eta_karma * k_norm
-            + p.eta_geo * w_bee
-            - p.eta_power * node.power_cost
-            - p.eta_bee * (phi / (p.phi_ref + 1e-12));
+        let mut trace = DutyUpdateTrace {
+            mass_term: p.eta_mass * m_norm,
+            karma_term: p.eta_karma * k_norm,
+            geo_term: p.eta_geo * w_bee,
+            power_penalty: p.eta_power * node.power_cost,
+            bee_penalty: p.eta_bee * (phi / (p.phi_ref + 1e-12)),
+            raw_u: 0.0,
+            projected_u: 0.0,
+        };
+        trace.raw_u = node.duty_cycle + trace.mass_term + trace.karma_term + trace.geo_term
+            - trace.power_penalty
+            - trace.bee_penalty;
 
         // Projection Π_[0,1]
-        if u < 0.0 {
-            u = 0.0;
-        } else if u > 1.0 {
-            u = 1.0;
+        let mut u = trace.raw_u.clamp(0.0, 1.0);
+
+        if node.essential && !node.bee_ctx.in_hive_exclusion {
+            // Essential nodes never fully shut off unless inside a hive exclusion zone.
+            u = u.max(p.min_essential_duty.clamp(0.0, 1.0));
         }
 
+        // Deny always means zero duty. An essential node held up by its floor is
+        // derated to that floor instead, however large the penalty.
+        let over_ref = phi >= p.phi_ref && !node.essential;
+        let verdict = if node.bee_ctx.in_hive_exclusion || over_ref || u <= 0.0 {
+            u = 0.0;
+            KernelVerdict::Deny
+        } else if phi > 0.0 {
+            KernelVerdict::Derate(u)
+        } else {
+            KernelVerdict::Permit
+        };
+        trace.projected_u = u;
+
         let eco_impact_bee = self.compute_eco_impact_bee(&node, phi);
-        let permitted = phi == 0.0 && !node.bee_ctx.in_hive_exclusion;
+        let permitted = matches!(verdict, KernelVerdict::Permit);
 
-        Ok(KernelDecision {
+        let decision = KernelDecision {
+            schema_version: CURRENT_SCHEMA_VERSION,
             node_id: node.node_id,
             safe_duty_cycle: u,
             permitted,
+            verdict,
             phi_penalty: phi,
+            phi_by_kind,
             eco_impact_bee,
-        })
+        };
+        Ok((decision, trace))
+    }
+}
+
+impl BeeSafetyKernel {
+    /// `evaluate_node`, then limit upward steps from `prev_duty` (the last
+    /// commanded duty cycle) to `max_duty_delta` to protect actuators. Downward
+    /// steps are never limited, so the result never exceeds what `evaluate_node`
+    /// computed, and a `Deny` is returned unchanged.
+    pub fn evaluate_node_rate_limited(
+        &self,
+        prev_duty: f64,
+        node: NodeState,
+    ) -> Result<KernelDecision, KernelError> {
+        let mut decision = self.evaluate_node(node)?;
+        if decision.verdict != KernelVerdict::Deny {
+            let d = self.params.max_duty_delta.max(0.0);
+            let cap = (prev_duty + d).max(0.0);
+            decision.safe_duty_cycle = decision.safe_duty_cycle.min(cap);
+        }
+        Ok(decision)
+    }
+
+    /// Node is in a hive exclusion zone and predicts any nonzero emission, as a
+    /// scalar level or in any spectrum bin.
+    fn exclusion_shutdown(node: &NodeState) -> bool {
+        let emitting = node.predicted_levels.iter().any(|pl| pl.level != 0.0)
+            || node.predicted_spectra.iter().flat_map(|sp| &sp.bins).any(|&(_, l)| l != 0.0);
+        node.bee_ctx.in_hive_exclusion && emitting
+    }
+
+    /// `evaluate_node` over a fleet, one result per node in input order.
+    pub fn evaluate_nodes(
+        &self,
+        nodes: Vec<NodeState>,
+    ) -> Vec<Result<KernelDecision, KernelError>> {
+        nodes.into_iter().map(|n| self.evaluate_node(n)).collect()
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl BeeSafetyKernel {
+    /// Parallel `evaluate_nodes`: nodes are independent and the kernel is
+    /// read-only, so each is evaluated on the rayon pool. Output keeps input order.
+    pub fn par_evaluate_nodes(
+        &self,
+        nodes: Vec<NodeState>,
+    ) -> Vec<Result<KernelDecision, KernelError>> {
+        use rayon::prelude::*;
+        nodes.into_par_iter().map(|n| self.evaluate_node(n)).collect()
+    }
+}
+
+/// Severity of a corridor violation for ranking incidents: `overage * sqrt(duration_secs)`.
+///
+/// Magnitude enters linearly and duration with a square-root weighting, so a
+/// sustained moderate breach can outrank a brief severe one, while doubling a
+/// breach's duration counts for less than doubling its overage.
+pub fn violation_severity(overage: f64, duration_secs: f64) -> f64 {
+    overage.max(0.0) * duration_secs.max(0.0).sqrt()
+}
+
+/// Projected fleet power draw: the sum of each node's `power_cost` scaled by its
+/// decision's `safe_duty_cycle`. Decisions and nodes are paired by position and
+/// must have matching node_ids.
+pub fn fleet_power_cost(decisions: &[KernelDecision], nodes: &[NodeState]) -> Result<f64, KernelError> {
+    if decisions.len() != nodes.len() {
+        return Err(KernelError::FleetSizeMismatch {
+            decisions: decisions.len(),
+            nodes: nodes.len(),
+        });
+    }
+    decisions.iter().zip(nodes).try_fold(0.0, |total, (d, n)| {
+        if d.node_id != n.node_id {
+            return Err(KernelError::NodeMismatch {
+                decision: d.node_id.clone(),
+                node: n.node_id.clone(),
+            });
+        }
+        Ok(total + n.power_cost * d.safe_duty_cycle)
+    })
+}
+
+/// Linear-trend forecaster over a short per-node history of predicted levels.
+#[derive(Debug, Clone, Default)]
+pub struct LevelForecaster {
+    /// (t_secs, level) samples per corridor kind, in insertion order.
+    history: HashMap<CorridorKind, Vec<(f64, f64)>>,
+}
+
+impl LevelForecaster {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the predicted levels observed at time `t_secs`.
+    pub fn record(&mut self, t_secs: f64, levels: &[PredictedLevels]) {
+        for pl in levels {
+            self.history.entry(pl.kind).or_default().push((t_secs, pl.level));
+        }
+    }
+
+    /// Least-squares fit level(t) = a + b t for one corridor kind; returns (a, b, t_last).
+    fn fit(&self, kind: CorridorKind) -> Option<(f64, f64, f64)> {
+        let samples = self.history.get(&kind)?;
+        if samples.len() < 2 {
+            return None;
+        }
+        let n = samples.len() as f64;
+        let t_mean = samples.iter().map(|(t, _)| t).sum::<f64>() / n;
+        let l_mean = samples.iter().map(|(_, l)| l).sum::<f64>() / n;
+        let mut s_tl = 0.0;
+        let mut s_tt = 0.0;
+        for (t, l) in samples {
+            s_tl += (t - t_mean) * (l - l_mean);
+            s_tt += (t - t_mean) * (t - t_mean);
+        }
+        if s_tt <= 0.0 {
+            return None;
+        }
+        let b = s_tl / s_tt;
+        let a = l_mean - b * t_mean;
+        let t_last = samples.iter().map(|(t, _)| *t).fold(f64::MIN, f64::max);
+        Some((a, b, t_last))
+    }
+
+    /// Time from the latest sample until the fitted trend crosses `l_max`.
+    /// Returns `None` for flat or declining trends, with fewer than two samples, or
+    /// when the slope is so shallow the crossing lies beyond what `Duration` holds.
+    pub fn time_to_breach(&self, kind: CorridorKind, l_max: f64) -> Option<Duration> {
+        let (a, b, t_last) = self.fit(kind)?;
+        if b <= 0.0 {
+            return None;
+        }
+        let level_now = a + b * t_last;
+        let secs = ((l_max - level_now) / b).max(0.0);
+        Duration::try_from_secs_f64(secs).ok()
+    }
+}
+
+impl BeeSafetyKernel {
+    /// Forecast when the node's `kind` corridor will breach this kernel's `l_max`.
+    pub fn forecast_breach(
+        &self,
+        forecaster: &LevelForecaster,
+        kind: CorridorKind,
+    ) -> Option<Duration> {
+        let env = self.envelope_for(kind)?;
+        forecaster.time_to_breach(kind, env.l_max)
+    }
+}
+
+#[cfg(feature = "otel")]
+impl BeeSafetyKernel {
+    /// `evaluate_node` wrapped in an OpenTelemetry span from the global tracer
+    /// provider, recording node_id, phi, permitted and safe_duty_cycle.
+    pub fn evaluate_node_traced(&self, node: NodeState) -> Result<KernelDecision, KernelError> {
+        use opentelemetry::trace::{Span, Status, Tracer};
+        use opentelemetry::{global, KeyValue};
+
+        let tracer = global::tracer("bee_safety_kernel");
+        let mut span = tracer.start("bee_safety_kernel.evaluate_node");
+        span.set_attribute(KeyValue::new("node_id", node.node_id.clone()));
+
+        let result = self.evaluate_node(node);
+        match &result {
+            Ok(d) => {
+                span.set_attribute(KeyValue::new("phi", d.phi_penalty));
+                span.set_attribute(KeyValue::new("permitted", d.permitted));
+                span.set_attribute(KeyValue::new("safe_duty_cycle", d.safe_duty_cycle));
+            }
+            Err(e) => span.set_status(Status::error(e.to_string())),
+        }
+        span.end();
+        result
     }
 }
 
@@ -208,10 +918,10 @@ mod tests {
     #[test]
     fn test_basic_kernel_allows_safe_node() {
         let envs = vec![
-            CorridorEnvelope { kind: CorridorKind::EMF, l_min: 0.0, l_max: 1.0 },
-            CorridorEnvelope { kind: CorridorKind::Thermal, l_min: 0.0, l_max: 2.0 },
-            CorridorEnvelope { kind: CorridorKind::Acoustic, l_min: 0.0, l_max: 60.0 },
-            CorridorEnvelope { kind: CorridorKind::Chemical, l_min: 0.0, l_max: 0.1 },
+            CorridorEnvelope::new(CorridorKind::EMF, 0.0, 1.0),
+            CorridorEnvelope::new(CorridorKind::Thermal, 0.0, 2.0),
+            CorridorEnvelope::new(CorridorKind::Acoustic, 0.0, 60.0),
+            CorridorEnvelope::new(CorridorKind::Chemical, 0.0, 0.1),
         ];
         let params = KernelParams {
             eta_mass: 0.05,
@@ -224,6 +934,10 @@ mod tests {
             phi_ref: 1.0,
             alpha_z: 0.05,
             beta_s: 0.7,
+            min_essential_duty: 0.1,
+            phi_shape: PhiShape::Quadratic,
+            hive_exclusion_penalty: DEFAULT_HIVE_EXCLUSION_PENALTY,
+            max_duty_delta: 1.0,
         };
         let kernel = BeeSafetyKernel::new(envs, params).unwrap();
 
@@ -238,17 +952,691 @@ mod tests {
                 bee_sensitivity: 2.0,
                 in_hive_exclusion: false,
                 dz_to_bee_band: 15.0,
+                sensitivity_by_kind: HashMap::new(),
             },
+            essential: false,
             predicted_levels: vec![
-                PredictedLevels { kind: CorridorKind::EMF, level: 0.3 },
-                PredictedLevels { kind: CorridorKind::Thermal, level: 1.0 },
-                PredictedLevels { kind: CorridorKind::Acoustic, level: 40.0 },
-                PredictedLevels { kind: CorridorKind::Chemical, level: 0.02 },
+                PredictedLevels { kind: CorridorKind::EMF, level: 0.3, unit: "V/m".into() },
+                PredictedLevels { kind: CorridorKind::Thermal, level: 1.0, unit: "C".into() },
+                PredictedLevels { kind: CorridorKind::Acoustic, level: 40.0, unit: "dB".into() },
+                PredictedLevels { kind: CorridorKind::Chemical, level: 0.02, unit: "mg/m3".into() },
             ],
+            predicted_spectra: Vec::new(),
         };
 
         let decision = kernel.evaluate_node(node).unwrap();
         assert!(decision.permitted);
         assert!(decision.safe_duty_cycle >= 0.0 && decision.safe_duty_cycle <= 1.0);
     }
+
+    fn test_envelopes() -> Vec<CorridorEnvelope> {
+        vec![
+            CorridorEnvelope::new(CorridorKind::EMF, 0.0, 1.0),
+            CorridorEnvelope::new(CorridorKind::Thermal, 0.0, 2.0),
+            CorridorEnvelope::new(CorridorKind::Acoustic, 0.0, 60.0),
+            CorridorEnvelope::new(CorridorKind::Chemical, 0.0, 0.1),
+        ]
+    }
+
+    fn test_params() -> KernelParams {
+        KernelParams {
+            eta_mass: 0.05,
+            eta_karma: 0.02,
+            eta_geo: 0.1,
+            eta_power: 0.05,
+            eta_bee: 0.2,
+            m_ref: 1e-6,
+            k_ref: 1e9,
+            phi_ref: 1.0,
+            alpha_z: 0.05,
+            beta_s: 0.7,
+            min_essential_duty: 0.1,
+            phi_shape: PhiShape::Quadratic,
+            hive_exclusion_penalty: DEFAULT_HIVE_EXCLUSION_PENALTY,
+            max_duty_delta: 1.0,
+        }
+    }
+
+    fn test_kernel() -> BeeSafetyKernel {
+        BeeSafetyKernel::new(test_envelopes(), test_params()).unwrap()
+    }
+
+    fn test_node() -> NodeState {
+        NodeState {
+            node_id: "CYB-AIR-CANOPY-01".to_string(),
+            duty_cycle: 0.5,
+            mass_removed_kg: 2e-6,
+            nano_karma_bytes: 5e9,
+            power_cost: 0.3,
+            cybo_weight: 0.8,
+            bee_ctx: BeeContext {
+                bee_sensitivity: 2.0,
+                in_hive_exclusion: false,
+                dz_to_bee_band: 15.0,
+                sensitivity_by_kind: HashMap::new(),
+            },
+            essential: false,
+            predicted_levels: vec![
+                PredictedLevels { kind: CorridorKind::EMF, level: 0.3, unit: "V/m".into() },
+                PredictedLevels { kind: CorridorKind::Thermal, level: 1.0, unit: "C".into() },
+                PredictedLevels { kind: CorridorKind::Acoustic, level: 40.0, unit: "dB".into() },
+                PredictedLevels { kind: CorridorKind::Chemical, level: 0.02, unit: "mg/m3".into() },
+            ],
+            predicted_spectra: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_phi_shape_keeps_ordering_changes_magnitude() {
+        let phi_for = |shape: PhiShape, emf: f64| {
+            let kernel = BeeSafetyKernel::new(
+                test_envelopes(),
+                KernelParams { phi_shape: shape, ..test_params() },
+            )
+            .unwrap();
+            let mut node = test_node();
+            set_level(&mut node, CorridorKind::EMF, emf);
+            kernel.compute_phi(&node).0
+        };
+
+        // EMF l_max is 1.0: excursions of 0.5 and 2.0.
+        let lin_small = phi_for(PhiShape::Linear, 1.5);
+        let lin_large = phi_for(PhiShape::Linear, 3.0);
+        let quad_small = phi_for(PhiShape::Quadratic, 1.5);
+        let quad_large = phi_for(PhiShape::Quadratic, 3.0);
+        assert!(lin_small < lin_large && quad_small < quad_large);
+        assert!((lin_large / lin_small - 4.0).abs() < 1e-9);
+        assert!((quad_large / quad_small - 16.0).abs() < 1e-9);
+        assert!(phi_for(PhiShape::Cubic, 3.0) > quad_large);
+    }
+
+    #[test]
+    fn test_normalized_risks_midpoint_is_half() {
+        let kernel = test_kernel();
+        let mut node = test_node();
+        set_level(&mut node, CorridorKind::Acoustic, 30.0);
+        set_level(&mut node, CorridorKind::EMF, 4.0);
+
+        let risks: HashMap<CorridorKind, f64> = kernel.normalized_risks(&node).into_iter().collect();
+        assert!((risks[&CorridorKind::Acoustic] - 0.5).abs() < 1e-12);
+        assert_eq!(risks[&CorridorKind::EMF], 1.0);
+        assert_eq!(risks.len(), node.predicted_levels.len());
+    }
+
+    #[test]
+    fn test_fleet_power_cost_sums_scaled_costs() {
+        let kernel = test_kernel();
+        let a = NodeState { node_id: "a".into(), power_cost: 0.4, ..test_node() };
+        let b = NodeState { node_id: "b".into(), power_cost: 0.8, ..test_node() };
+        let mut decisions = vec![
+            kernel.evaluate_node(a.clone()).unwrap(),
+            kernel.evaluate_node(b.clone()).unwrap(),
+        ];
+        decisions[0].safe_duty_cycle = 0.5;
+        decisions[1].safe_duty_cycle = 0.25;
+
+        let nodes = vec![a, b];
+        let total = fleet_power_cost(&decisions, &nodes).unwrap();
+        assert!((total - (0.4 * 0.5 + 0.8 * 0.25)).abs() < 1e-12);
+
+        decisions.swap(0, 1);
+        assert!(matches!(
+            fleet_power_cost(&decisions, &nodes),
+            Err(KernelError::NodeMismatch { .. })
+        ));
+        assert!(matches!(
+            fleet_power_cost(&decisions[..1], &nodes),
+            Err(KernelError::FleetSizeMismatch { decisions: 1, nodes: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_polytope_round_trips_through_named_format() {
+        // distance >= 50 m, o3 <= 40 ppb, duty <= 0.8.
+        let polytope = BeerightsPolytope {
+            constraints: vec![
+                ([-1.0, 0.0, 0.0, 0.0], 50.0),
+                ([0.0, 1.0, 0.0, 0.0], -40.0),
+                ([0.0, 0.0, 0.0, 1.0], -0.8),
+            ],
+        };
+        let json = serde_json::to_string(&polytope.to_named()).unwrap();
+        for axis in POLYTOPE_AXES {
+            assert!(json.contains(axis), "missing axis {axis} in {json}");
+        }
+
+        let named: NamedPolytope = serde_json::from_str(&json).unwrap();
+        assert_eq!(named.constraints[1].normal.o3_ppb, 1.0);
+        let restored = BeerightsPolytope::from_named(&named);
+        assert_eq!(restored, polytope);
+        assert!(restored.contains(&[100.0, 30.0, 0.5, 0.5]));
+        assert!(!restored.contains(&[10.0, 30.0, 0.5, 0.5]));
+    }
+
+    #[test]
+    fn test_phi_by_kind_localizes_chemical_violation() {
+        let kernel = test_kernel();
+        let mut node = test_node();
+        set_level(&mut node, CorridorKind::Chemical, 0.3);
+        let decision = kernel.evaluate_node(node).unwrap();
+
+        assert!(!decision.permitted);
+        assert_eq!(decision.phi_by_kind.len(), 4);
+        let nonzero: Vec<_> = decision
+            .phi_by_kind
+            .iter()
+            .filter(|(_, phi)| **phi > 0.0)
+            .map(|(kind, _)| *kind)
+            .collect();
+        assert_eq!(nonzero, vec![CorridorKind::Chemical]);
+        assert_eq!(decision.phi_by_kind[&CorridorKind::Chemical], decision.phi_penalty);
+    }
+
+    #[test]
+    fn test_new_rejects_duplicate_envelopes() {
+        let mut envs = test_envelopes();
+        envs.push(CorridorEnvelope::new(CorridorKind::EMF, 0.0, 0.5));
+        let err = BeeSafetyKernel::new(envs, test_params()).err().unwrap();
+        assert!(matches!(err, KernelError::DuplicateEnvelope(CorridorKind::EMF)));
+        assert!(BeeSafetyKernel::new(test_envelopes(), test_params()).is_ok());
+    }
+
+    fn fleet(n: usize) -> Vec<NodeState> {
+        (0..n)
+            .map(|i| {
+                let mut node = test_node();
+                node.node_id = format!("CYB-AIR-{:03}", i);
+                node.duty_cycle = (i % 11) as f64 / 10.0;
+                set_level(&mut node, CorridorKind::EMF, 0.5 + (i % 7) as f64 * 0.2);
+                node
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_evaluate_nodes_preserves_order() {
+        let kernel = test_kernel();
+        let mut nodes = fleet(100);
+        nodes[42].duty_cycle = 1.5;
+        let results = kernel.evaluate_nodes(nodes.clone());
+
+        assert_eq!(results.len(), 100);
+        for (node, result) in nodes.iter().zip(&results) {
+            match result {
+                Ok(d) => assert_eq!(d.node_id, node.node_id),
+                Err(e) => assert_eq!(*e, KernelError::InvalidDutyCycle(1.5)),
+            }
+        }
+        assert!(results[42].is_err());
+        assert_eq!(results.iter().filter(|r| r.is_err()).count(), 1);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_evaluate_nodes_matches_serial() {
+        let kernel = test_kernel();
+        let mut nodes = fleet(100);
+        nodes[7].duty_cycle = -0.1;
+        assert_eq!(kernel.par_evaluate_nodes(nodes.clone()), kernel.evaluate_nodes(nodes));
+    }
+
+    #[test]
+    fn test_strict_units_rejects_mismatched_level() {
+        let mut node = test_node();
+        node.predicted_levels[0].unit = "dB".into();
+
+        assert!(test_kernel().evaluate_node(node.clone()).is_ok());
+        let strict = test_kernel().with_strict_units();
+        assert_eq!(
+            strict.evaluate_node(node).unwrap_err(),
+            KernelError::UnitMismatch {
+                kind: CorridorKind::EMF,
+                expected: "V/m".into(),
+                got: "dB".into(),
+            }
+        );
+        assert!(strict.evaluate_node(test_node()).is_ok());
+    }
+
+    #[test]
+    fn test_strict_units_follow_envelope_declared_unit() {
+        let mut envelopes = test_envelopes();
+        envelopes[0].unit = "mV/m".into();
+        let strict = BeeSafetyKernel::new(envelopes, test_params())
+            .unwrap()
+            .with_strict_units();
+        assert_eq!(
+            strict.evaluate_node(test_node()).unwrap_err(),
+            KernelError::UnitMismatch {
+                kind: CorridorKind::EMF,
+                expected: "mV/m".into(),
+                got: "V/m".into(),
+            }
+        );
+        let mut node = test_node();
+        node.predicted_levels[0].unit = "mV/m".into();
+        assert!(strict.evaluate_node(node).is_ok());
+
+        let legacy: CorridorEnvelope =
+            serde_json::from_str(r#"{"kind":"EMF","l_max":1.0,"l_min":0.0}"#).unwrap();
+        assert_eq!(legacy.unit(), "V/m");
+    }
+
+    #[test]
+    fn test_hive_exclusion_emission_forces_zero_duty() {
+        let mut node = test_node();
+        node.bee_ctx.in_hive_exclusion = true;
+        node.essential = true;
+        node.duty_cycle = 1.0;
+        node.mass_removed_kg = 1e-3;
+
+        let decision = test_kernel().evaluate_node(node.clone()).unwrap();
+        assert_eq!(decision.safe_duty_cycle, 0.0);
+        assert!(!decision.permitted);
+
+        // In-envelope levels give zero phi regardless of the penalty, so the
+        // configurable penalty only scales actual excursions.
+        set_level(&mut node, CorridorKind::EMF, 1.5);
+        let small = BeeSafetyKernel::new(
+            test_envelopes(),
+            KernelParams { hive_exclusion_penalty: 10.0, ..test_params() },
+        )
+        .unwrap();
+        assert!((small.evaluate_node(node).unwrap().phi_penalty - 2.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_missing_thermal_prediction_is_rejected() {
+        let mut node = test_node();
+        node.predicted_levels.retain(|pl| pl.kind != CorridorKind::Thermal);
+
+        assert_eq!(
+            test_kernel().evaluate_node(node).unwrap_err(),
+            KernelError::MissingPrediction(CorridorKind::Thermal)
+        );
+    }
+
+    #[test]
+    fn test_rate_limited_duty_step_is_capped() {
+        let mut node = test_node();
+        node.mass_removed_kg = 1e-5;
+        let raw = test_kernel().evaluate_node(node.clone()).unwrap().safe_duty_cycle;
+        assert_eq!(raw, 1.0);
+
+        let params = KernelParams { max_duty_delta: 0.2, ..test_params() };
+        let kernel = BeeSafetyKernel::new(test_envelopes(), params).unwrap();
+        let step = |prev: f64, node: NodeState| {
+            kernel.evaluate_node_rate_limited(prev, node).unwrap().safe_duty_cycle
+        };
+        assert!((step(0.5, node.clone()) - 0.7).abs() < 1e-12);
+        // Within the cap the raw update passes through unchanged.
+        assert_eq!(step(0.9, node.clone()), 1.0);
+
+        // A lower derate is taken as computed, not eased down from prev.
+        let mut derated = test_node();
+        set_level(&mut derated, CorridorKind::EMF, 1.6);
+        let decision = kernel.evaluate_node(derated.clone()).unwrap();
+        assert!(matches!(decision.verdict, KernelVerdict::Derate(_)));
+        let computed = decision.safe_duty_cycle;
+        assert!(computed < 0.7);
+        assert_eq!(step(0.9, derated), computed);
+
+        // A breach drops the duty straight to zero, however high it was.
+        let mut breached = node.clone();
+        set_level(&mut breached, CorridorKind::Chemical, 5.0);
+        let decision = kernel.evaluate_node_rate_limited(0.9, breached).unwrap();
+        assert_eq!(decision.safe_duty_cycle, 0.0);
+        assert_eq!(decision.verdict, KernelVerdict::Deny);
+
+        node.bee_ctx.in_hive_exclusion = true;
+        assert_eq!(step(0.9, node), 0.0);
+    }
+
+    #[test]
+    fn test_duty_update_trace_sums_to_raw_u() {
+        let node = test_node();
+        let (decision, trace) = test_kernel().evaluate_node_with_trace(node.clone()).unwrap();
+
+        assert_eq!(
+            trace.raw_u,
+            node.duty_cycle + trace.mass_term + trace.karma_term + trace.geo_term
+                - trace.power_penalty
+                - trace.bee_penalty
+        );
+        assert!((trace.mass_term - 0.1).abs() < 1e-6);
+        assert_eq!(trace.projected_u, decision.safe_duty_cycle);
+        assert_eq!(decision, test_kernel().evaluate_node(node.clone()).unwrap());
+
+        // An exclusion shutdown skips the update, so no penalty terms are reported.
+        let mut excluded = node;
+        excluded.bee_ctx.in_hive_exclusion = true;
+        let (decision, trace) = test_kernel().evaluate_node_with_trace(excluded).unwrap();
+        assert_eq!(decision.safe_duty_cycle, 0.0);
+        assert_eq!(trace, DutyUpdateTrace::default());
+    }
+
+    #[test]
+    fn test_co2_corridor_breach_adds_phi() {
+        let mut envs = test_envelopes();
+        envs.push(CorridorEnvelope::new(CorridorKind::Co2, 0.0, 1000.0));
+        let kernel = BeeSafetyKernel::new(envs, test_params()).unwrap();
+        let mut node = test_node();
+        node.predicted_levels.push(PredictedLevels {
+            kind: CorridorKind::Co2,
+            level: 800.0,
+            unit: CorridorKind::Co2.unit().into(),
+        });
+        assert_eq!(kernel.evaluate_node(node.clone()).unwrap().phi_penalty, 0.0);
+
+        set_level(&mut node, CorridorKind::Co2, 1500.0);
+        let decision = kernel.evaluate_node(node).unwrap();
+        assert!(decision.phi_penalty > 0.0);
+        assert_eq!(decision.phi_by_kind[&CorridorKind::Co2], decision.phi_penalty);
+        assert!(!decision.permitted);
+    }
+
+    #[test]
+    fn test_eco_impact_non_decreasing_in_mass_removed() {
+        let kernel = test_kernel();
+        let scores: Vec<f64> = [0.0, 5e-7, 1e-6, 2e-6, 1e-5, 1e-3]
+            .iter()
+            .map(|&kg| {
+                let mut node = test_node();
+                node.mass_removed_kg = kg;
+                kernel.evaluate_node(node).unwrap().eco_impact_bee
+            })
+            .collect();
+
+        assert!(scores.windows(2).all(|w| w[1] >= w[0]), "{:?}", scores);
+        assert!(scores[5] > scores[1]);
+        assert!(scores.iter().all(|s| (0.0..=1.0).contains(s)));
+    }
+
+    #[test]
+    fn test_verdict_permit_derate_deny() {
+        let kernel = test_kernel();
+        let decision = kernel.evaluate_node(test_node()).unwrap();
+        assert_eq!(decision.verdict, KernelVerdict::Permit);
+        assert!(decision.permitted);
+
+        let mut node = test_node();
+        set_level(&mut node, CorridorKind::EMF, 1.2);
+        let decision = kernel.evaluate_node(node.clone()).unwrap();
+        assert!(decision.phi_penalty > 0.0 && decision.phi_penalty < 1.0);
+        assert_eq!(decision.verdict, KernelVerdict::Derate(decision.safe_duty_cycle));
+        assert!(decision.safe_duty_cycle > 0.0);
+        assert!(!decision.permitted);
+
+        set_level(&mut node, CorridorKind::EMF, 4.0);
+        assert_eq!(kernel.evaluate_node(node.clone()).unwrap().verdict, KernelVerdict::Deny);
+        // A looser phi_ref turns the same breach into a derate.
+        let loose = BeeSafetyKernel::new(
+            test_envelopes(),
+            KernelParams { phi_ref: 100.0, ..test_params() },
+        )
+        .unwrap();
+        assert!(matches!(loose.evaluate_node(node).unwrap().verdict, KernelVerdict::Derate(_)));
+
+        let mut excluded = test_node();
+        excluded.bee_ctx.in_hive_exclusion = true;
+        assert_eq!(kernel.evaluate_node(excluded).unwrap().verdict, KernelVerdict::Deny);
+
+        // Every Deny carries zero duty, even when the update term alone would not.
+        let mut heavy = test_node();
+        heavy.mass_removed_kg = 1e-3;
+        set_level(&mut heavy, CorridorKind::EMF, 2.0);
+        let decision = kernel.evaluate_node(heavy).unwrap();
+        assert!(decision.phi_penalty >= kernel.params.phi_ref);
+        assert_eq!(decision.verdict, KernelVerdict::Deny);
+        assert_eq!(decision.safe_duty_cycle, 0.0);
+    }
+
+    #[test]
+    fn test_serde_round_trips_and_schema_version() {
+        let params = serde_json::to_string(&test_params()).unwrap();
+        let back: KernelParams = serde_json::from_str(&params).unwrap();
+        assert_eq!(serde_json::to_string(&back).unwrap(), params);
+        let node = serde_json::to_string(&test_node()).unwrap();
+        let back: NodeState = serde_json::from_str(&node).unwrap();
+        assert_eq!(serde_json::to_string(&back).unwrap(), node);
+
+        let decision = test_kernel().evaluate_node(test_node()).unwrap();
+        assert_eq!(decision.schema_version, CURRENT_SCHEMA_VERSION);
+        let json = serde_json::to_string(&decision).unwrap();
+        assert_eq!(KernelDecision::from_json(&json).unwrap(), decision);
+
+        // A decision stored before versioning has no schema_version (or newer fields).
+        let legacy = r#"{"node_id":"n1","safe_duty_cycle":0.4,"permitted":true,
+            "phi_penalty":0.0,"eco_impact_bee":0.8}"#;
+        let old = KernelDecision::from_json(legacy).unwrap();
+        assert_eq!(old.schema_version, 0);
+        assert!(old.phi_by_kind.is_empty());
+
+        let future = json.replace(
+            "\"schema_version\":1",
+            &format!("\"schema_version\":{}", CURRENT_SCHEMA_VERSION + 1),
+        );
+        assert_eq!(
+            KernelDecision::from_json(&future).unwrap_err(),
+            KernelError::UnsupportedSchema { found: 2, supported: CURRENT_SCHEMA_VERSION }
+        );
+        assert!(matches!(KernelDecision::from_json("{"), Err(KernelError::InvalidJson(_))));
+    }
+
+    #[test]
+    fn test_spectrum_bin_trips_penalty_average_does_not() {
+        let band = |f_ghz_min, f_ghz_max, l_max| SpectralEnvelope {
+            kind: CorridorKind::EMF,
+            f_ghz_min,
+            f_ghz_max,
+            l_min: 0.0,
+            l_max,
+        };
+        let kernel = test_kernel()
+            .with_spectral_envelopes(vec![band(0.7, 0.9, 2.0), band(2.4, 2.5, 0.5)])
+            .unwrap();
+        let bins = vec![(0.8, 0.2), (0.85, 0.2), (2.45, 0.8)];
+        let mean = bins.iter().map(|(_, l)| l).sum::<f64>() / bins.len() as f64;
+
+        // The aggregate level sits inside both the scalar and the 2.4 GHz envelope.
+        let mut node = test_node();
+        set_level(&mut node, CorridorKind::EMF, mean);
+        assert!(mean < 0.5);
+        assert_eq!(kernel.evaluate_node(node.clone()).unwrap().phi_penalty, 0.0);
+
+        node.predicted_spectra.push(PredictedSpectrum { kind: CorridorKind::EMF, bins });
+        let decision = kernel.evaluate_node(node).unwrap();
+        // Only the 2.45 GHz bin breaches: 0.3^2 weighted by bee sensitivity 2.
+        assert!((decision.phi_penalty - 0.18).abs() < 1e-9);
+        assert_eq!(decision.phi_by_kind[&CorridorKind::EMF], decision.phi_penalty);
+    }
+
+    #[test]
+    fn test_spectrum_only_prediction_must_be_covered() {
+        let band = |f_ghz_min, f_ghz_max, l_min, l_max| SpectralEnvelope {
+            kind: CorridorKind::EMF,
+            f_ghz_min,
+            f_ghz_max,
+            l_min,
+            l_max,
+        };
+        let kernel = test_kernel().with_spectral_envelopes(vec![band(2.4, 2.5, 0.0, 0.5)]).unwrap();
+        let mut node = test_node();
+        node.predicted_levels.retain(|pl| pl.kind != CorridorKind::EMF);
+        let spectrum = |bins: Vec<(f64, f64)>| PredictedSpectrum { kind: CorridorKind::EMF, bins };
+
+        // A 5 GHz bin has no envelope, so the spectrum cannot replace the scalar EMF level.
+        node.predicted_spectra = vec![spectrum(vec![(2.45, 0.2), (5.0, 9.0)])];
+        let missing = KernelError::MissingPrediction(CorridorKind::EMF);
+        assert_eq!(kernel.evaluate_node(node.clone()).unwrap_err(), missing);
+        // Nor can any spectrum on a kernel without spectral envelopes.
+        assert_eq!(test_kernel().evaluate_node(node.clone()).unwrap_err(), missing);
+
+        node.predicted_spectra = vec![spectrum(vec![(2.45, 0.2)])];
+        assert!(kernel.evaluate_node(node.clone()).unwrap().permitted);
+
+        // Spectrum-only emission inside a hive exclusion still shuts the node off.
+        for pl in &mut node.predicted_levels {
+            pl.level = 0.0;
+        }
+        node.bee_ctx.in_hive_exclusion = true;
+        node.essential = true;
+        assert_eq!(kernel.evaluate_node(node).unwrap().safe_duty_cycle, 0.0);
+
+        let inverted = test_kernel().with_spectral_envelopes(vec![band(2.5, 2.4, 0.0, 0.5)]);
+        assert!(matches!(inverted, Err(KernelError::InvalidSpectralEnvelope { .. })));
+        let inverted = test_kernel().with_spectral_envelopes(vec![band(2.4, 2.5, 1.0, 0.5)]);
+        assert!(matches!(inverted, Err(KernelError::InvalidSpectralEnvelope { .. })));
+    }
+
+    fn set_level(node: &mut NodeState, kind: CorridorKind, level: f64) {
+        for pl in node.predicted_levels.iter_mut().filter(|pl| pl.kind == kind) {
+            pl.level = level;
+        }
+    }
+
+    #[test]
+    fn test_forecaster_rising_emf_breach() {
+        let kernel = test_kernel();
+
+        let mut forecaster = LevelForecaster::new();
+        for (i, level) in [0.4, 0.5, 0.6, 0.7].iter().enumerate() {
+            forecaster.record(
+                60.0 * i as f64,
+                &[PredictedLevels { kind: CorridorKind::EMF, level: *level, unit: "V/m".into() }],
+            );
+        }
+
+        // Rising 0.1 per minute from 0.7 towards 1.0 → about three minutes left.
+        let ttb = kernel.forecast_breach(&forecaster, CorridorKind::EMF).unwrap();
+        assert!((ttb.as_secs_f64() - 180.0).abs() < 1e-6);
+
+        // Flat trend never breaches.
+        let mut flat = LevelForecaster::new();
+        let steady = [PredictedLevels { kind: CorridorKind::EMF, level: 0.5, unit: "V/m".into() }];
+        flat.record(0.0, &steady);
+        flat.record(60.0, &steady);
+        assert!(kernel.forecast_breach(&flat, CorridorKind::EMF).is_none());
+    }
+
+    #[test]
+    fn test_forecaster_near_zero_slope_does_not_panic() {
+        let kernel = test_kernel();
+        let mut creeping = LevelForecaster::new();
+        for (t, level) in [(0.0, 0.0), (1.0, 1e-300)] {
+            let pl = PredictedLevels { kind: CorridorKind::EMF, level, unit: "V/m".into() };
+            creeping.record(t, &[pl]);
+        }
+        // Positive slope, but the crossing is ~1e300 s away: no representable Duration.
+        assert!(kernel.forecast_breach(&creeping, CorridorKind::EMF).is_none());
+    }
+
+    #[test]
+    fn test_eco_impact_sensitivity_chemical_dominates() {
+        let kernel = test_kernel();
+        let mut node = test_node();
+        set_level(&mut node, CorridorKind::Chemical, 0.5);
+        set_level(&mut node, CorridorKind::Thermal, 2.1);
+
+        let sens = kernel.eco_impact_sensitivity(&node);
+        let chem = sens[&CorridorKind::Chemical];
+        assert!(chem > 0.0);
+        assert!(sens.iter().all(|(k, v)| *k == CorridorKind::Chemical || *v < chem));
+    }
+
+    #[test]
+    fn test_cylinder_exclusion_covers_beyond_inscribed_sphere() {
+        let cylinder = ExclusionShape::Cylinder { center_xy: [0.0, 0.0], radius: 5.0, z_min: 0.0, z_max: 20.0 };
+        let sphere = ExclusionShape::Sphere { center: [0.0, 0.0, 10.0], radius: 5.0 };
+        let map = HiveExclusionMap { zones: vec![cylinder] };
+
+        let point = [1.0, 1.0, 18.0];
+        assert!(map.contains(point));
+        assert!(!sphere.contains(point));
+        assert!(!map.contains([6.0, 0.0, 10.0]));
+    }
+
+    #[test]
+    fn test_violation_severity_long_moderate_outranks_brief_severe() {
+        let long_moderate = violation_severity(0.5, 3600.0);
+        let brief_severe = violation_severity(2.0, 60.0);
+        assert!(long_moderate > brief_severe);
+        assert_eq!(violation_severity(-1.0, 3600.0), 0.0);
+    }
+
+    #[test]
+    fn test_essential_node_keeps_duty_floor() {
+        let kernel = test_kernel();
+        let mut node = test_node();
+        set_level(&mut node, CorridorKind::Chemical, 5.0);
+
+        let plain = kernel.evaluate_node(node.clone()).unwrap();
+        assert_eq!(plain.safe_duty_cycle, 0.0);
+
+        node.essential = true;
+        let essential = kernel.evaluate_node(node.clone()).unwrap();
+        assert_eq!(essential.safe_duty_cycle, kernel.params.min_essential_duty);
+        assert_eq!(essential.verdict, KernelVerdict::Derate(kernel.params.min_essential_duty));
+
+        node.bee_ctx.in_hive_exclusion = true;
+        assert_eq!(kernel.evaluate_node(node).unwrap().safe_duty_cycle, 0.0);
+    }
+
+    #[test]
+    fn test_sensitivity_by_kind_weights_acoustic_breach() {
+        let envs = vec![
+            CorridorEnvelope::new(CorridorKind::Thermal, 0.0, 2.0),
+            CorridorEnvelope::new(CorridorKind::Acoustic, 0.0, 2.0),
+        ];
+        let kernel = BeeSafetyKernel::new(envs, test_params()).unwrap();
+        let mut node = test_node();
+        node.bee_ctx.sensitivity_by_kind = [(CorridorKind::Acoustic, 5.0)].into_iter().collect();
+        node.bee_ctx.bee_sensitivity = 1.0;
+
+        node.predicted_levels = vec![
+            PredictedLevels { kind: CorridorKind::Thermal, level: 1.0, unit: "C".into() },
+            PredictedLevels { kind: CorridorKind::Acoustic, level: 2.2, unit: "dB".into() },
+        ];
+        let acoustic_phi = kernel.evaluate_node(node.clone()).unwrap().phi_penalty;
+
+        node.predicted_levels = vec![
+            PredictedLevels { kind: CorridorKind::Thermal, level: 2.2, unit: "C".into() },
+            PredictedLevels { kind: CorridorKind::Acoustic, level: 1.0, unit: "dB".into() },
+        ];
+        let thermal_phi = kernel.evaluate_node(node).unwrap().phi_penalty;
+
+        assert!(acoustic_phi > thermal_phi);
+        assert!((acoustic_phi / thermal_phi - 5.0).abs() < 1e-9);
+    }
+
+    #[cfg(feature = "otel")]
+    #[test]
+    fn test_evaluate_node_traced_emits_span_per_evaluation() {
+        use opentelemetry::{global, Value};
+        use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider};
+
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        global::set_tracer_provider(provider);
+
+        let kernel = test_kernel();
+        let decision = kernel.evaluate_node_traced(test_node()).unwrap();
+        kernel.evaluate_node_traced(test_node()).unwrap();
+
+        let spans = exporter.get_finished_spans().unwrap();
+        assert_eq!(spans.len(), 2);
+        let attr = |key: &str| {
+            spans[0]
+                .attributes
+                .iter()
+                .find(|kv| kv.key.as_str() == key)
+                .map(|kv| kv.value.clone())
+        };
+        assert_eq!(attr("node_id"), Some(Value::from(decision.node_id.clone())));
+        assert_eq!(attr("phi"), Some(Value::F64(decision.phi_penalty)));
+        assert_eq!(attr("permitted"), Some(Value::Bool(decision.permitted)));
+        assert_eq!(attr("safe_duty_cycle"), Some(Value::F64(decision.safe_duty_cycle)));
+    }
 }
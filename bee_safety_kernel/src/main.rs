@@ -1,11 +1,13 @@
+use std::collections::HashMap;
+
 use bee_safety_kernel::*;
 
 fn main() {
     let envs = vec![
-        CorridorEnvelope { kind: CorridorKind::EMF, l_min: 0.0, l_max: 1.0 },
-        CorridorEnvelope { kind: CorridorKind::Thermal, l_min: 0.0, l_max: 2.0 },
-        CorridorEnvelope { kind: CorridorKind::Acoustic, l_min: 0.0, l_max: 60.0 },
-        CorridorEnvelope { kind: CorridorKind::Chemical, l_min: 0.0, l_max: 0.1 },
+        CorridorEnvelope::new(CorridorKind::EMF, 0.0, 1.0),
+        CorridorEnvelope::new(CorridorKind::Thermal, 0.0, 2.0),
+        CorridorEnvelope::new(CorridorKind::Acoustic, 0.0, 60.0),
+        CorridorEnvelope::new(CorridorKind::Chemical, 0.0, 0.1),
     ];
     let params = KernelParams {
         eta_mass: 0.05,
@@ -18,6 +20,10 @@ fn main() {
         phi_ref: 1.0,
         alpha_z: 0.05,
         beta_s: 0.7,
+        min_essential_duty: 0.1,
+        phi_shape: PhiShape::Quadratic,
+        hive_exclusion_penalty: DEFAULT_HIVE_EXCLUSION_PENALTY,
+        max_duty_delta: 1.0,
     };
     let kernel = BeeSafetyKernel::new(envs, params).expect("kernel init");
 
@@ -32,13 +38,16 @@ fn main() {
             bee_sensitivity: 2.5,
             in_hive_exclusion: false,
             dz_to_bee_band: 10.0,
+            sensitivity_by_kind: HashMap::new(),
         },
+        essential: false,
         predicted_levels: vec![
-            PredictedLevels { kind: CorridorKind::EMF, level: 0.4 },
-            PredictedLevels { kind: CorridorKind::Thermal, level: 1.2 },
-            PredictedLevels { kind: CorridorKind::Acoustic, level: 45.0 },
-            PredictedLevels { kind: CorridorKind::Chemical, level: 0.03 },
+            PredictedLevels { kind: CorridorKind::EMF, level: 0.4, unit: "V/m".into() },
+            PredictedLevels { kind: CorridorKind::Thermal, level: 1.2, unit: "C".into() },
+            PredictedLevels { kind: CorridorKind::Acoustic, level: 45.0, unit: "dB".into() },
+            PredictedLevels { kind: CorridorKind::Chemical, level: 0.03, unit: "mg/m3".into() },
         ],
+        predicted_spectra: Vec::new(),
     };
 
     let decision = kernel.evaluate_node(node).expect("decision");
@@ -14,6 +14,28 @@ pub struct RFMeasurement {
     pub e_vpm: f64,
 }
 
+/// Tolerance for treating two envelope fields as equal in `dedup_envelopes`.
+const ENVELOPE_EPS: f64 = 1e-9;
+
+fn same_envelope(a: &RFEnvelope, b: &RFEnvelope) -> bool {
+    (a.f_ghz_min - b.f_ghz_min).abs() <= ENVELOPE_EPS
+        && (a.f_ghz_max - b.f_ghz_max).abs() <= ENVELOPE_EPS
+        && (a.e_base_vpm - b.e_base_vpm).abs() <= ENVELOPE_EPS
+        && (a.e_no_effect_vpm - b.e_no_effect_vpm).abs() <= ENVELOPE_EPS
+}
+
+/// Drop envelopes equal (within `ENVELOPE_EPS`) in all four fields to an
+/// earlier one, keeping first occurrences in order.
+pub fn dedup_envelopes(envs: &[RFEnvelope]) -> Vec<RFEnvelope> {
+    let mut out: Vec<RFEnvelope> = Vec::with_capacity(envs.len());
+    for e in envs {
+        if !out.iter().any(|kept| same_envelope(kept, e)) {
+            out.push(*e);
+        }
+    }
+    out
+}
+
 fn in_band(b: &RFEnvelope, f: f64) -> bool {
     f >= b.f_ghz_min && f <= b.f_ghz_max
 }
@@ -46,3 +68,31 @@ pub fn rf_permit(
     let r = r_rf(envs, meas);
     r < r_hard
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dedup_envelopes_keeps_distinct() {
+        let wifi = RFEnvelope {
+            f_ghz_min: 2.4,
+            f_ghz_max: 2.5,
+            e_base_vpm: 0.1,
+            e_no_effect_vpm: 3.0,
+        };
+        let mut wifi_copy = wifi;
+        wifi_copy.e_no_effect_vpm += 1e-12;
+        let lte = RFEnvelope {
+            f_ghz_min: 0.7,
+            f_ghz_max: 0.9,
+            e_base_vpm: 0.2,
+            e_no_effect_vpm: 5.0,
+        };
+
+        let deduped = dedup_envelopes(&[wifi, wifi_copy, lte]);
+        assert_eq!(deduped.len(), 2);
+        assert_eq!(deduped[0].f_ghz_min, 2.4);
+        assert_eq!(deduped[1].f_ghz_min, 0.7);
+    }
+}
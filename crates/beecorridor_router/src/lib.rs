@@ -0,0 +1,1136 @@
+use std::cmp::Reverse;
+
+use chrono::{SecondsFormat, Utc};
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+
+use bee_safety_kernel::{BeeSafetyKernel, CorridorKind, NodeState};
+use hive_guard::hive::{evaluate_hive, HiveCorridors};
+use ecosocialbee_core::HumanEcoProxy;
+use hive_guard::{
+    classify_risk, EcoBand, HiveEnvelope, HiveGuardError, HiveInnerLedger, HiveSystemAdjustment,
+};
+
+#[derive(Debug, Clone)]
+pub enum HumanTaskKind {
+    FarmingSprayReduction,
+    PlantWildflowers,
+    AdjustIrrigation,
+    DimLights,
+    ReduceNoise,
+}
+
+#[derive(Debug, Clone)]
+pub struct HumanTask {
+    pub id: String,
+    pub kind: HumanTaskKind,
+    pub eco_reward_hint: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct RoutedTask {
+    pub task: HumanTask,
+    pub hive_id: String,
+    pub accepted: bool,
+    pub reason: String,
+}
+
+/// Combines the hive-safety verdict with eco gain into a final acceptance.
+/// Safety is always a hard gate; the weights only rank or filter safe options.
+#[derive(Debug, Clone)]
+pub struct AcceptancePolicy {
+    /// Score contributed by passing all hive safety invariants.
+    pub safety_weight: f32,
+    /// Score per point of eco-impact gain (delta_eco_impact_score_corridor).
+    pub eco_weight: f32,
+    /// Minimum combined score a safe adjustment needs to be accepted.
+    pub min_score: f32,
+}
+
+impl Default for AcceptancePolicy {
+    /// Safety-only gate: every safe adjustment is accepted.
+    fn default() -> Self {
+        Self {
+            safety_weight: 1.0,
+            eco_weight: 0.0,
+            min_score: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AcceptanceDecision {
+    pub accepted: bool,
+    pub score: f32,
+}
+
+impl AcceptancePolicy {
+    /// Eco-weighted policy: a safe adjustment must deliver at least `min_eco_gain`.
+    pub fn eco_weighted(min_eco_gain: f32) -> Self {
+        Self {
+            safety_weight: 0.0,
+            eco_weight: 1.0,
+            min_score: min_eco_gain,
+        }
+    }
+
+    pub fn combine(&self, safety_ok: bool, eco_gain: f32) -> AcceptanceDecision {
+        if !safety_ok {
+            return AcceptanceDecision {
+                accepted: false,
+                score: 0.0,
+            };
+        }
+        let score = self.safety_weight + self.eco_weight * eco_gain;
+        AcceptanceDecision {
+            accepted: score >= self.min_score,
+            score,
+        }
+    }
+}
+
+pub fn task_to_adjustment(task: &HumanTask, hive: &HiveEnvelope) -> HiveSystemAdjustment {
+    let now = Utc::now();
+    match task.kind {
+        HumanTaskKind::FarmingSprayReduction => HiveSystemAdjustment {
+            id: format!("adj-{}-{}", hive.hive_id, task.id),
+            timestamp: now,
+            hive_id: hive.hive_id.clone(),
+            delta_pesticide_exposure_ppb: -10.0,
+            delta_shade_fraction: 0.0,
+            delta_water_availability_index: 0.0,
+            delta_forage_radius_m: 0.0,
+            delta_forage_diversity_index: 0.05,
+            delta_artificial_light_nits: 0.0,
+            delta_noise_db: 0.0,
+            delta_eco_impact_score_corridor: 5.0,
+        },
+        HumanTaskKind::PlantWildflowers => HiveSystemAdjustment {
+            id: format!("adj-{}-{}", hive.hive_id, task.id),
+            timestamp: now,
+            hive_id: hive.hive_id.clone(),
+            delta_pesticide_exposure_ppb: 0.0,
+            delta_shade_fraction: 0.0,
+            delta_water_availability_index: 0.1,
+            delta_forage_radius_m: 200.0,
+            delta_forage_diversity_index: 0.15,
+            delta_artificial_light_nits: 0.0,
+            delta_noise_db: 0.0,
+            delta_eco_impact_score_corridor: 10.0,
+        },
+        HumanTaskKind::AdjustIrrigation => HiveSystemAdjustment {
+            id: format!("adj-{}-{}", hive.hive_id, task.id),
+            timestamp: now,
+            hive_id: hive.hive_id.clone(),
+            delta_pesticide_exposure_ppb: 0.0,
+            delta_shade_fraction: 0.0,
+            delta_water_availability_index: 0.1,
+            delta_forage_radius_m: 0.0,
+            delta_forage_diversity_index: 0.02,
+            delta_artificial_light_nits: 0.0,
+            delta_noise_db: 0.0,
+            delta_eco_impact_score_corridor: 2.0,
+        },
+        HumanTaskKind::DimLights => HiveSystemAdjustment {
+            id: format!("adj-{}-{}", hive.hive_id, task.id),
+            timestamp: now,
+            hive_id: hive.hive_id.clone(),
+            delta_pesticide_exposure_ppb: 0.0,
+            delta_shade_fraction: 0.0,
+            delta_water_availability_index: 0.0,
+            delta_forage_radius_m: 0.0,
+            delta_forage_diversity_index: 0.0,
+            delta_artificial_light_nits: -50.0,
+            delta_noise_db: 0.0,
+            delta_eco_impact_score_corridor: 1.0,
+        },
+        HumanTaskKind::ReduceNoise => HiveSystemAdjustment {
+            id: format!("adj-{}-{}", hive.hive_id, task.id),
+            timestamp: now,
+            hive_id: hive.hive_id.clone(),
+            delta_pesticide_exposure_ppb: 0.0,
+            delta_shade_fraction: 0.0,
+            delta_water_availability_index: 0.0,
+            delta_forage_radius_m: 0.0,
+            delta_forage_diversity_index: 0.0,
+            delta_artificial_light_nits: 0.0,
+            delta_noise_db: -10.0,
+            delta_eco_impact_score_corridor: 1.0,
+        },
+    }
+}
+
+/// Pesticide exposure drop (ppb) per liter of avoided spray.
+pub const PROXY_PPB_PER_SPRAY_L: f32 = 0.5;
+/// Forage radius gain (m) per m2 of pollinator habitat.
+pub const PROXY_RADIUS_M_PER_HABITAT_M2: f32 = 0.2;
+/// Forage diversity gain per m2 of habitat at quality 1.0.
+pub const PROXY_DIVERSITY_PER_HABITAT_M2: f32 = 1.5e-4;
+/// Artificial light drop (nits) per hour of reduced light pollution.
+pub const PROXY_NITS_PER_DARK_HOUR: f32 = 5.0;
+/// Noise drop (dB) per hour of reduced noise pollution.
+pub const PROXY_DB_PER_QUIET_HOUR: f32 = 1.0;
+
+/// Estimate the landscape change a logged community action implies for a nearby
+/// hive, scaled with the `PROXY_*` rates (calibrated against `task_to_adjustment`:
+/// 1000 m2 of habitat matches one wildflower planting). Negative proxy inputs
+/// count as zero, so every delta keeps the sign `check_adjustment` requires; the
+/// eco gain credits pesticide and habitat at the same rates as the task mapping.
+/// The id carries the actor and the proxy timestamp, so each logged action is its
+/// own ledger idempotency key and only a resubmitted proxy replays.
+pub fn proxy_to_adjustment(proxy: &HumanEcoProxy, hive_id: &str) -> HiveSystemAdjustment {
+    let spray_l = proxy.reduced_spray_volume_l.max(0.0);
+    let habitat_m2 = proxy.pollinator_habitat_area_m2.max(0.0);
+    let quality = proxy.pollinator_habitat_quality_index.clamp(0.0, 1.0);
+
+    let delta_pesticide_exposure_ppb = -PROXY_PPB_PER_SPRAY_L * spray_l;
+    let delta_forage_radius_m = PROXY_RADIUS_M_PER_HABITAT_M2 * habitat_m2;
+    let delta_forage_diversity_index =
+        (PROXY_DIVERSITY_PER_HABITAT_M2 * habitat_m2 * quality).min(1.0);
+    HiveSystemAdjustment {
+        id: format!(
+            "adj-{}-proxy-{}-{}",
+            hive_id,
+            proxy.actor_human_id,
+            proxy.timestamp.to_rfc3339_opts(SecondsFormat::Micros, true)
+        ),
+        timestamp: proxy.timestamp,
+        hive_id: hive_id.to_string(),
+        delta_pesticide_exposure_ppb,
+        delta_shade_fraction: 0.0,
+        delta_water_availability_index: 0.0,
+        delta_forage_radius_m,
+        delta_forage_diversity_index,
+        delta_artificial_light_nits: -PROXY_NITS_PER_DARK_HOUR
+            * proxy.reduced_light_pollution_hours.max(0.0),
+        delta_noise_db: -PROXY_DB_PER_QUIET_HOUR * proxy.reduced_noise_pollution_hours.max(0.0),
+        // -10 ppb earns 5 points; 0.15 diversity earns 10.
+        delta_eco_impact_score_corridor: -0.5 * delta_pesticide_exposure_ppb
+            + (10.0 / 0.15) * delta_forage_diversity_index,
+    }
+}
+
+/// Generate every task's adjustment for every hive and check the sign constraints
+/// up front, so a wrong mapping in `task_to_adjustment` surfaces before ledger time.
+/// Returns (adjustment id, violation) pairs; empty when all mappings are sound.
+pub fn validate_task_adjustments(
+    tasks: &[HumanTask],
+    hives: &[HiveEnvelope],
+) -> Vec<(String, HiveGuardError)> {
+    validate_adjustments_with(tasks, hives, task_to_adjustment)
+}
+
+fn validate_adjustments_with(
+    tasks: &[HumanTask],
+    hives: &[HiveEnvelope],
+    to_adjustment: impl Fn(&HumanTask, &HiveEnvelope) -> HiveSystemAdjustment,
+) -> Vec<(String, HiveGuardError)> {
+    let mut violations = Vec::new();
+    for task in tasks {
+        for hive in hives {
+            let adj = to_adjustment(task, hive);
+            for err in adj.sign_violations() {
+                violations.push((adj.id.clone(), err));
+            }
+        }
+    }
+    violations
+}
+
+pub fn route_tasks_through_corridors(
+    tasks: &[HumanTask],
+    hives: &mut [HiveEnvelope],
+) -> Vec<RoutedTask> {
+    route_tasks_with_policy(tasks, hives, &AcceptancePolicy::default())
+}
+
+/// Route tasks like `route_tasks_through_corridors`, additionally requiring each
+/// safe adjustment to pass the acceptance policy before it enters the ledger. The
+/// policy sees the ledger's real invariant check; an unsafe adjustment is reported
+/// with its first violation.
+pub fn route_tasks_with_policy(
+    tasks: &[HumanTask],
+    hives: &mut [HiveEnvelope],
+    policy: &AcceptancePolicy,
+) -> Vec<RoutedTask> {
+    let mut rng = thread_rng();
+    let mut ledger = HiveInnerLedger::new();
+    let mut results = Vec::new();
+
+    for task in tasks {
+        // Prefer hives in worse risk bands to receive protective actions first.
+        let mut candidates = hives.to_vec();
+        candidates.sort_by_key(|h| Reverse(classify_risk(h)));
+
+        let mut routed = None;
+
+        for hive in candidates.iter_mut() {
+            let adj = task_to_adjustment(task, hive);
+            let violations = ledger.check_adjustment(hive, &adj);
+            let decision =
+                policy.combine(violations.is_empty(), adj.delta_eco_impact_score_corridor);
+            if !decision.accepted {
+                let reason = match violations.first() {
+                    Some(err) => format!("Rejected by hive ledger: {}", err),
+                    None => format!(
+                        "Rejected by acceptance policy: score {:.2} below {:.2}",
+                        decision.score, policy.min_score
+                    ),
+                };
+                routed.get_or_insert(RoutedTask {
+                    task: task.clone(),
+                    hive_id: hive.hive_id.clone(),
+                    accepted: false,
+                    reason,
+                });
+                continue;
+            }
+            match ledger.apply_adjustment(hive.clone(), adj) {
+                Ok(new_env) => {
+                    // Update original hive entry.
+                    if let Some(orig) = hives.iter_mut().find(|h| h.hive_id == new_env.hive_id) {
+                        *orig = new_env;
+                    }
+
+                    routed = Some(RoutedTask {
+                        task: task.clone(),
+                        hive_id: hive.hive_id.clone(),
+                        accepted: true,
+                        reason: "Adjustment satisfies all hive safety invariants".into(),
+                    });
+                    break;
+                }
+                Err(err) => {
+                    // Try next hive; keep explanation for debug.
+                    routed.get_or_insert(RoutedTask {
+                        task: task.clone(),
+                        hive_id: hive.hive_id.clone(),
+                        accepted: false,
+                        reason: format!("Rejected by hive ledger: {}", err),
+                    });
+                }
+            }
+        }
+
+        if let Some(r) = routed {
+            results.push(r);
+        } else {
+            let hive_id = hives
+                .choose(&mut rng)
+                .map(|h| h.hive_id.clone())
+                .unwrap_or_else(|| "none".into());
+            results.push(RoutedTask {
+                task: task.clone(),
+                hive_id,
+                accepted: false,
+                reason: "No hive could accept adjustment under safety invariants".into(),
+            });
+        }
+    }
+
+    results
+}
+
+/// Multi-step router: holds hives and a task backlog, decays ambient and
+/// per-species toxins as time advances, and each step routes the highest-priority
+/// pending task (largest `eco_reward_hint`) to the neediest hive that accepts it.
+/// Neediness orders by `evaluate_band`, then by `evaluate_band_weighted` stress score.
+#[derive(Debug)]
+pub struct Scheduler {
+    pub hives: Vec<HiveEnvelope>,
+    pub backlog: Vec<HumanTask>,
+    /// Ambient and per-species toxin half-life applied between steps.
+    pub toxin_half_life_hours: f32,
+    /// Simulated time advanced by each `step`.
+    pub step_hours: f32,
+    pub elapsed_hours: f32,
+    pub ledger: HiveInnerLedger,
+}
+
+impl Scheduler {
+    pub fn new(
+        hives: Vec<HiveEnvelope>,
+        backlog: Vec<HumanTask>,
+        toxin_half_life_hours: f32,
+        step_hours: f32,
+    ) -> Self {
+        Self {
+            hives,
+            backlog,
+            toxin_half_life_hours,
+            step_hours,
+            elapsed_hours: 0.0,
+            ledger: HiveInnerLedger::new(),
+        }
+    }
+
+    /// Advance time by `step_hours`, then route one task. Returns the routed task,
+    /// or `None` if no pending task is accepted by any hive (the backlog is kept).
+    pub fn step(&mut self) -> Option<RoutedTask> {
+        self.advance(self.step_hours);
+
+        let mut order: Vec<usize> = (0..self.backlog.len()).collect();
+        order.sort_by(|&a, &b| {
+            self.backlog[b]
+                .eco_reward_hint
+                .total_cmp(&self.backlog[a].eco_reward_hint)
+        });
+        let needy = neediness_order(&self.hives);
+
+        for t in order {
+            for &h in &needy {
+                let adj = task_to_adjustment(&self.backlog[t], &self.hives[h]);
+                if let Ok(post) = self.ledger.apply_adjustment(self.hives[h].clone(), adj) {
+                    self.hives[h] = post;
+                    let task = self.backlog.remove(t);
+                    return Some(RoutedTask {
+                        task,
+                        hive_id: self.hives[h].hive_id.clone(),
+                        accepted: true,
+                        reason: format!("Scheduled at t={:.1}h", self.elapsed_hours),
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// Decay ambient and per-species toxins by first-order kinetics over `hours`
+    /// and refresh bands.
+    fn advance(&mut self, hours: f32) {
+        self.elapsed_hours += hours;
+        let factor = if self.toxin_half_life_hours > 0.0 {
+            0.5f32.powf(hours / self.toxin_half_life_hours)
+        } else {
+            1.0
+        };
+        for hive in &mut self.hives {
+            hive.ambient_toxin_ppb *= factor;
+            for (_, ppb) in &mut hive.toxins {
+                *ppb *= factor;
+            }
+            hive.eco_band = hive.evaluate_band();
+        }
+    }
+}
+
+/// Indices of `hives`, neediest first: worse `evaluate_band`, then higher
+/// `evaluate_band_weighted` stress score.
+fn neediness_order(hives: &[HiveEnvelope]) -> Vec<usize> {
+    let mut needy: Vec<usize> = (0..hives.len()).collect();
+    needy.sort_by(|&a, &b| {
+        let (ha, hb) = (&hives[a], &hives[b]);
+        let score = |h: &HiveEnvelope| h.evaluate_band_weighted().1;
+        hb.evaluate_band()
+            .cmp(&ha.evaluate_band())
+            .then(score(hb).total_cmp(&score(ha)))
+    });
+    needy
+}
+
+/// How many times `task` must be applied, each time to the neediest hive whose
+/// ledger accepts it, before the fleet's mean `effective_eco_impact_score` reaches
+/// `target_mean_eco`. `Some(0)` if already there; `None` if the task adds no eco
+/// gain or no hive accepts it before the target is reached.
+pub fn interventions_to_target(
+    hives: &[HiveEnvelope],
+    task: &HumanTask,
+    target_mean_eco: f32,
+) -> Option<usize> {
+    let mut hives = hives.to_vec();
+    let mean = |hives: &[HiveEnvelope]| {
+        hives.iter().map(|h| h.effective_eco_impact_score()).sum::<f32>() / hives.len() as f32
+    };
+    if hives.is_empty() {
+        return None;
+    }
+    let mut ledger = HiveInnerLedger::new();
+    let mut count = 0;
+    while mean(&hives) < target_mean_eco {
+        let applied = neediness_order(&hives).into_iter().find_map(|i| {
+            let mut adj = task_to_adjustment(task, &hives[i]);
+            // Each repeat is a distinct intervention, not a replay of the last one.
+            adj.id = format!("{}-{}", adj.id, count + 1);
+            if adj.delta_eco_impact_score_corridor <= 0.0 {
+                return None;
+            }
+            ledger.apply_adjustment(hives[i].clone(), adj).ok().map(|post| (i, post))
+        });
+        let (i, post) = applied?;
+        hives[i] = post;
+        count += 1;
+    }
+    Some(count)
+}
+
+/// Monte Carlo estimate of the chance `task` routes successfully: the fraction of
+/// sampled hive states whose ledger accepts the task's adjustment. 0.0 if no samples.
+pub fn acceptance_probability(task: &HumanTask, hive_samples: &[HiveEnvelope]) -> f64 {
+    if hive_samples.is_empty() {
+        return 0.0;
+    }
+    let accepted = hive_samples
+        .iter()
+        .filter(|hive| {
+            let adj = task_to_adjustment(task, hive);
+            HiveInnerLedger::new()
+                .apply_adjustment((*hive).clone(), adj)
+                .is_ok()
+        })
+        .count();
+    accepted as f64 / hive_samples.len() as f64
+}
+
+/// Run a `Scheduler` (without toxin decay) until the backlog drains or stalls, and
+/// record each task's outcome as its accepting hive, or `None` if never scheduled.
+fn scheduled_outcomes(tasks: &[HumanTask], hives: &[HiveEnvelope]) -> Vec<Option<String>> {
+    let mut scheduler = Scheduler::new(hives.to_vec(), tasks.to_vec(), 0.0, 1.0);
+    let mut outcomes = vec![None; tasks.len()];
+    while let Some(routed) = scheduler.step() {
+        if let Some(i) = tasks.iter().position(|t| t.id == routed.task.id) {
+            outcomes[i] = Some(routed.hive_id);
+        }
+    }
+    outcomes
+}
+
+/// For each task, re-run scheduling with its `eco_reward_hint` shifted by
+/// `+perturbation` and by `-perturbation` (other hints unchanged), and report
+/// whether either shift changes that task's accepted/hive outcome.
+/// Returns (task id, flipped) pairs in task order.
+pub fn routing_reward_sensitivity(
+    tasks: &[HumanTask],
+    hives: &[HiveEnvelope],
+    perturbation: f32,
+) -> Vec<(String, bool)> {
+    let baseline = scheduled_outcomes(tasks, hives);
+    tasks
+        .iter()
+        .enumerate()
+        .map(|(i, task)| {
+            let flipped = [perturbation, -perturbation].iter().any(|delta| {
+                let mut perturbed = tasks.to_vec();
+                perturbed[i].eco_reward_hint += delta;
+                scheduled_outcomes(&perturbed, hives)[i] != baseline[i]
+            });
+            (task.id.clone(), flipped)
+        })
+        .collect()
+}
+
+/// Pick a small set of tasks (indices into `tasks`) that brings every Warning or
+/// Critical hive to Safe, using the greedy set-cover heuristic: repeatedly take the
+/// task that dry-runs a single adjustment to Safe on the most still-uncovered hives.
+///
+/// The result is approximate (within a ln(n) factor of the true minimum), only
+/// considers one task per hive, and leaves out hives no single task can rescue.
+pub fn min_intervention_cover(hives: &[HiveEnvelope], tasks: &[HumanTask]) -> Vec<usize> {
+    let at_risk: Vec<&HiveEnvelope> = hives
+        .iter()
+        .filter(|h| h.evaluate_band() != EcoBand::Safe)
+        .collect();
+
+    // covers[t] = indices into `at_risk` that task t alone would bring to Safe.
+    let covers: Vec<Vec<usize>> = tasks
+        .iter()
+        .map(|task| {
+            at_risk
+                .iter()
+                .enumerate()
+                .filter(|(_, hive)| {
+                    let adj = task_to_adjustment(task, hive);
+                    HiveInnerLedger::new()
+                        .apply_adjustment(HiveEnvelope::clone(hive), adj)
+                        .map(|post| post.evaluate_band() == EcoBand::Safe)
+                        .unwrap_or(false)
+                })
+                .map(|(i, _)| i)
+                .collect()
+        })
+        .collect();
+
+    let mut uncovered = vec![true; at_risk.len()];
+    let mut chosen = Vec::new();
+    loop {
+        let best = covers
+            .iter()
+            .enumerate()
+            .filter(|(t, _)| !chosen.contains(t))
+            .map(|(t, c)| (t, c.iter().filter(|&&h| uncovered[h]).count()))
+            .filter(|&(_, gain)| gain > 0)
+            .fold(None, |best: Option<(usize, usize)>, cand| match best {
+                Some(b) if b.1 >= cand.1 => Some(b),
+                _ => Some(cand),
+            });
+        let Some((t, _)) = best else { break };
+        for &h in &covers[t] {
+            uncovered[h] = false;
+        }
+        chosen.push(t);
+    }
+    chosen
+}
+
+/// Combined kernel/hive view of why a node near a stressed hive is blocked.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnosis {
+    /// Kernel corridor with the highest normalized risk, and that risk in [0, 1].
+    pub binding_kernel_corridor: Option<(CorridorKind, f64)>,
+    /// Hive corridor closest to its hard limit, and its margin `1 - r` in [0, 1].
+    pub tightest_hive_corridor: Option<(&'static str, f64)>,
+    /// Both point at the same physical cause (thermal or chemical).
+    pub shared_cause: bool,
+}
+
+/// Kernel corridor kind a hive band measures, if any.
+fn hive_corridor_kind(corridors: &HiveCorridors, var_id: &str) -> Option<CorridorKind> {
+    if var_id == corridors.temp_bands.var_id || var_id == corridors.brood_temp_bands.var_id {
+        Some(CorridorKind::Thermal)
+    } else if var_id == corridors.toxin_air_bands.var_id
+        || var_id == corridors.toxin_wax_bands.var_id
+    {
+        Some(CorridorKind::Chemical)
+    } else {
+        None
+    }
+}
+
+/// Diagnose a node against both the kernel's envelopes and the nearby hive's
+/// corridors, flagging when the binding constraints share a cause.
+pub fn diagnose(
+    node: &NodeState,
+    hive: &hive_guard::hive::HiveEnvelope,
+    kernel: &BeeSafetyKernel,
+    corridors: &HiveCorridors,
+) -> Diagnosis {
+    let binding_kernel_corridor = kernel
+        .normalized_risks(node)
+        .into_iter()
+        .fold(None, |best: Option<(CorridorKind, f64)>, cand| match best {
+            Some(b) if b.1 >= cand.1 => Some(b),
+            _ => Some(cand),
+        });
+    let tightest = evaluate_hive(hive, corridors)
+        .coords
+        .into_iter()
+        .max_by(|a, b| a.value.total_cmp(&b.value));
+
+    let shared_cause = match (binding_kernel_corridor, &tightest) {
+        (Some((kind, _)), Some(c)) => hive_corridor_kind(corridors, c.var_id) == Some(kind),
+        _ => false,
+    };
+    Diagnosis {
+        binding_kernel_corridor,
+        tightest_hive_corridor: tightest.map(|c| (c.var_id, 1.0 - c.value.clamp(0.0, 1.0))),
+        shared_cause,
+    }
+}
+
+/// Quote `s` as a DOT string literal, escaping backslashes, quotes and newlines.
+fn dot_quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Render routing results as a Graphviz DOT digraph: one node per task, one per
+/// hive, and one edge per task-to-hive attempt labeled `accepted` or `rejected`.
+pub fn routing_to_dot(results: &[RoutedTask]) -> String {
+    let mut dot = String::from("digraph routing {\n    rankdir=LR;\n");
+    let mut hives: Vec<&str> = Vec::new();
+    for r in results {
+        if !hives.contains(&r.hive_id.as_str()) {
+            hives.push(&r.hive_id);
+        }
+    }
+
+    for r in results {
+        dot.push_str(&format!(
+            "    {} [shape=box, label={}];\n",
+            dot_quote(&format!("task:{}", r.task.id)),
+            dot_quote(&format!("{} ({:?})", r.task.id, r.task.kind)),
+        ));
+    }
+    for h in &hives {
+        dot.push_str(&format!(
+            "    {} [shape=ellipse, label={}];\n",
+            dot_quote(&format!("hive:{}", h)),
+            dot_quote(h),
+        ));
+    }
+    for r in results {
+        let (label, color) = if r.accepted { ("accepted", "green") } else { ("rejected", "red") };
+        dot.push_str(&format!(
+            "    {} -> {} [label={}, color={}, tooltip={}];\n",
+            dot_quote(&format!("task:{}", r.task.id)),
+            dot_quote(&format!("hive:{}", r.hive_id)),
+            dot_quote(label),
+            color,
+            dot_quote(&r.reason),
+        ));
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use bee_safety_kernel::{
+        BeeContext, CorridorEnvelope, KernelParams, PhiShape, PredictedLevels,
+    };
+    use hive_guard::bands::CorridorBands;
+
+    use super::*;
+
+    fn kernel() -> BeeSafetyKernel {
+        let envs = vec![
+            CorridorEnvelope::new(CorridorKind::EMF, 0.0, 1.0),
+            CorridorEnvelope::new(CorridorKind::Thermal, 0.0, 2.0),
+            CorridorEnvelope::new(CorridorKind::Chemical, 0.0, 0.1),
+        ];
+        let params = KernelParams {
+            eta_mass: 0.05,
+            eta_karma: 0.02,
+            eta_geo: 0.1,
+            eta_power: 0.05,
+            eta_bee: 0.2,
+            m_ref: 1e-6,
+            k_ref: 1e9,
+            phi_ref: 1.0,
+            alpha_z: 0.05,
+            beta_s: 0.7,
+            min_essential_duty: 0.1,
+            phi_shape: PhiShape::Quadratic,
+            hive_exclusion_penalty: bee_safety_kernel::DEFAULT_HIVE_EXCLUSION_PENALTY,
+            max_duty_delta: 1.0,
+        };
+        BeeSafetyKernel::new(envs, params).unwrap()
+    }
+
+    fn node(levels: &[(CorridorKind, f64)]) -> NodeState {
+        NodeState {
+            node_id: "CYB-AIR-HIVE-EDGE".into(),
+            duty_cycle: 0.5,
+            mass_removed_kg: 0.0,
+            nano_karma_bytes: 0.0,
+            power_cost: 0.3,
+            cybo_weight: 0.8,
+            bee_ctx: BeeContext {
+                bee_sensitivity: 2.0,
+                in_hive_exclusion: false,
+                dz_to_bee_band: 5.0,
+                sensitivity_by_kind: HashMap::new(),
+            },
+            essential: false,
+            predicted_levels: levels
+                .iter()
+                .map(|&(kind, level)| PredictedLevels { kind, level, unit: kind.unit().into() })
+                .collect(),
+            predicted_spectra: Vec::new(),
+        }
+    }
+
+    fn core_hive(hive_temp_c: f64, toxin_index_air: f64) -> hive_guard::hive::HiveEnvelope {
+        hive_guard::hive::HiveEnvelope {
+            hive_id: "hive-edge".into(),
+            region: "meadow".into(),
+            brood_temp_c: 35.0,
+            hive_temp_c,
+            hive_humidity_pct: 60.0,
+            nectar_kg: 20.0,
+            pollen_kg: 5.0,
+            forager_load_pct: 0.5,
+            toxin_index_air,
+            toxin_index_wax: 5.0,
+            forage_radius_km: 2.0,
+            eco_band: hive_guard::hive::EcoBand::Warning,
+        }
+    }
+
+    fn corridors() -> HiveCorridors {
+        HiveCorridors {
+            temp_bands: CorridorBands::new("hive_temp_c", "C", 35.0, 36.0, 38.0, 1.0, 0, true),
+            brood_temp_bands: CorridorBands::new("brood_temp_c", "C", 35.0, 35.5, 37.0, 1.0, 1, true),
+            humidity_bands: CorridorBands::new("hive_humidity_pct", "%", 70.0, 80.0, 95.0, 0.5, 2, true),
+            toxin_air_bands: CorridorBands::new("toxin_index_air", "ppb", 25.0, 40.0, 60.0, 2.0, 3, true),
+            toxin_wax_bands: CorridorBands::new("toxin_index_wax", "ppb", 10.0, 20.0, 40.0, 1.0, 4, true),
+            forage_radius_bands: CorridorBands::new("forage_radius_km", "km", 3.0, 4.0, 6.0, 0.5, 5, true),
+            forager_load_bands: CorridorBands::new("forager_load_pct", "fraction", 0.8, 0.9, 1.0, 0.5, 6, true),
+        }
+    }
+
+    #[test]
+    fn test_diagnose_flags_shared_thermal_cause() {
+        let hot_node = node(&[
+            (CorridorKind::EMF, 0.2),
+            (CorridorKind::Thermal, 1.9),
+            (CorridorKind::Chemical, 0.01),
+        ]);
+        let d = diagnose(&hot_node, &core_hive(37.5, 20.0), &kernel(), &corridors());
+
+        let (kind, risk) = d.binding_kernel_corridor.unwrap();
+        assert_eq!(kind, CorridorKind::Thermal);
+        assert!((risk - 0.95).abs() < 1e-12);
+        let (var_id, margin) = d.tightest_hive_corridor.unwrap();
+        assert_eq!(var_id, "hive_temp_c");
+        assert!(margin > 0.0 && margin < 0.5);
+        assert!(d.shared_cause);
+
+        // Same node, but the hive is stressed by toxins instead.
+        let d = diagnose(&hot_node, &core_hive(35.0, 55.0), &kernel(), &corridors());
+        assert_eq!(d.tightest_hive_corridor.unwrap().0, "toxin_index_air");
+        assert!(!d.shared_cause);
+    }
+
+    #[test]
+    fn test_eco_weighted_policy_rejects_safe_zero_gain() {
+        let safety_only = AcceptancePolicy::default();
+        let eco = AcceptancePolicy::eco_weighted(1.0);
+
+        assert!(safety_only.combine(true, 0.0).accepted);
+        assert!(!eco.combine(true, 0.0).accepted);
+        assert!(eco.combine(true, 5.0).accepted);
+        // Safety stays a hard gate regardless of eco gain.
+        assert!(!eco.combine(false, 50.0).accepted);
+    }
+
+    #[test]
+    fn test_route_with_policy_gates_on_real_safety_check() {
+        let task = |id: &str, kind| HumanTask {
+            id: id.into(),
+            kind,
+            eco_reward_hint: 1.0,
+        };
+        let policy = AcceptancePolicy::eco_weighted(0.5);
+
+        // Dimming lights earns eco gain, but the hive's forage radius is already
+        // under its minimum, so the safety verdict must reject it.
+        let mut hives = vec![hive("narrow", 20.0, 0.8, 500.0)];
+        let routed =
+            route_tasks_with_policy(&[task("dim", HumanTaskKind::DimLights)], &mut hives, &policy);
+        assert!(!routed[0].accepted);
+        assert_eq!(
+            routed[0].reason,
+            format!("Rejected by hive ledger: {}", HiveGuardError::ReducesForageRadius)
+        );
+        assert_eq!(hives[0], hive("narrow", 20.0, 0.8, 500.0));
+
+        // A safe adjustment below the eco threshold is the policy's call.
+        let strict = AcceptancePolicy::eco_weighted(5.0);
+        let mut hives = vec![hive("wide", 20.0, 0.8, 1500.0)];
+        let routed =
+            route_tasks_with_policy(&[task("dim", HumanTaskKind::DimLights)], &mut hives, &strict);
+        assert!(!routed[0].accepted);
+        assert!(routed[0].reason.starts_with("Rejected by acceptance policy"));
+    }
+
+    #[test]
+    fn test_routing_to_dot_has_annotated_edge_per_attempt() {
+        let task = |id: &str| HumanTask {
+            id: id.into(),
+            kind: HumanTaskKind::DimLights,
+            eco_reward_hint: 1.0,
+        };
+        let results = vec![
+            RoutedTask {
+                task: task("t1"),
+                hive_id: "hive-a".into(),
+                accepted: true,
+                reason: "ok".into(),
+            },
+            RoutedTask {
+                task: task("t\"2"),
+                hive_id: "hive-b".into(),
+                accepted: false,
+                reason: "Rejected by hive ledger: \"noise\"\nline".into(),
+            },
+        ];
+
+        let dot = routing_to_dot(&results);
+        assert!(dot.starts_with("digraph routing {"));
+        assert!(dot.contains("\"task:t1\" -> \"hive:hive-a\" [label=\"accepted\""));
+        assert!(dot.contains("\"task:t\\\"2\" -> \"hive:hive-b\" [label=\"rejected\""));
+        assert!(dot.contains("\\\"noise\\\"\\nline"));
+        assert_eq!(dot.matches(" -> ").count(), results.len());
+    }
+
+    fn hive(id: &str, toxin: f32, diversity: f32, radius: f32) -> HiveEnvelope {
+        let mut env = HiveEnvelope {
+            hive_id: id.into(),
+            brood_frames: 8,
+            nectar_kg: 12.0,
+            pollen_kg: 4.5,
+            hive_temperature_c: 34.0,
+            forager_load: 0.7,
+            ambient_toxin_ppb: toxin,
+            forage_diversity_index: diversity,
+            forage_radius_m: radius,
+            eco_band: EcoBand::Safe,
+            eco_impact_score_corridor: 60.0,
+            safe_temperature_c_min: 32.0,
+            safe_temperature_c_max: 36.0,
+            safe_toxin_ppb_max: 50.0,
+            safe_forage_diversity_index_min: 0.5,
+            safe_forage_radius_m_min: 1000.0,
+            recovery_eco_threshold: None,
+            toxins: Vec::new(),
+            safe_toxin_ppb_max_by_species: HashMap::new(),
+            shade_cooling_coeff_c: hive_guard::DEFAULT_SHADE_COOLING_COEFF_C,
+            safe_forager_load_max: None,
+            forager_load_policy: hive_guard::ForagerLoadPolicy::HardBand,
+        };
+        env.eco_band = env.evaluate_band();
+        env
+    }
+
+    #[test]
+    fn test_min_intervention_cover_picks_overlapping_tasks() {
+        let hives = vec![
+            hive("toxic", 55.0, 0.8, 1500.0),
+            hive("sparse", 20.0, 0.46, 1500.0),
+            hive("narrow", 20.0, 0.8, 900.0),
+            hive("fine", 20.0, 0.8, 1500.0),
+        ];
+        let task = |id: &str, kind| HumanTask {
+            id: id.into(),
+            kind,
+            eco_reward_hint: 1.0,
+        };
+        let tasks = vec![
+            task("irrigate", HumanTaskKind::AdjustIrrigation),
+            task("spray", HumanTaskKind::FarmingSprayReduction),
+            task("flowers", HumanTaskKind::PlantWildflowers),
+            task("spray-again", HumanTaskKind::FarmingSprayReduction),
+        ];
+
+        // spray covers {toxic, sparse}, flowers covers {sparse, narrow}: two tasks suffice.
+        let cover = min_intervention_cover(&hives, &tasks);
+        assert_eq!(cover, vec![1, 2]);
+        assert!(min_intervention_cover(&hives[3..], &tasks).is_empty());
+    }
+
+    #[test]
+    fn test_proxy_to_adjustment_maps_spray_reduction() {
+        let proxy =
+            HumanEcoProxy::new("grower-7", Utc::now(), 0.0, 0.0, 0.0, 0.0, 0.0, 3, 40.0, 0.0, 0.0);
+        let adj = proxy_to_adjustment(&proxy, "hive-a");
+        assert_eq!(adj.hive_id, "hive-a");
+        assert_eq!(adj.delta_pesticide_exposure_ppb, -20.0);
+        assert_eq!(adj.delta_forage_radius_m, 0.0);
+        assert_eq!(adj.delta_eco_impact_score_corridor, 10.0);
+        assert!(adj.sign_violations().is_empty());
+
+        let habitat =
+            HumanEcoProxy::new("gardener-2", Utc::now(), 0.0, 0.0, 0.0, 1000.0, 1.0, 0, 0.0, 4.0, 2.0);
+        let adj = proxy_to_adjustment(&habitat, "hive-a");
+        assert_eq!(adj.delta_pesticide_exposure_ppb, 0.0);
+        assert_eq!(adj.delta_forage_radius_m, 200.0);
+        assert!((adj.delta_forage_diversity_index - 0.15).abs() < 1e-6);
+        assert_eq!(adj.delta_artificial_light_nits, -20.0);
+        assert_eq!(adj.delta_noise_db, -2.0);
+        assert!(adj.sign_violations().is_empty());
+    }
+
+    #[test]
+    fn test_proxy_ids_distinguish_repeat_actions_by_one_actor() {
+        let at = |hour| chrono::DateTime::<Utc>::UNIX_EPOCH + chrono::Duration::hours(hour);
+        let monday =
+            HumanEcoProxy::new("grower-7", at(0), 0.0, 0.0, 0.0, 0.0, 0.0, 1, 10.0, 0.0, 0.0);
+        let tuesday = HumanEcoProxy { timestamp: at(24), ..monday.clone() };
+        let first = proxy_to_adjustment(&monday, "hive-a");
+        let second = proxy_to_adjustment(&tuesday, "hive-a");
+        assert_ne!(first.id, second.id);
+        assert_eq!(first.id, proxy_to_adjustment(&monday, "hive-a").id);
+
+        // Both actions land in the ledger; resubmitting Monday's is a no-op replay.
+        let mut ledger = HiveInnerLedger::new();
+        let env = hive("hive-a", 40.0, 0.8, 1500.0);
+        let after_monday = ledger.apply_adjustment(env.clone(), first.clone()).unwrap();
+        ledger.apply_adjustment(after_monday.clone(), second).unwrap();
+        assert_eq!(ledger.apply_adjustment(env, first).unwrap(), after_monday);
+        assert_eq!(ledger.events().len(), 2);
+    }
+
+    #[test]
+    fn test_validate_task_adjustments_catches_wrong_sign() {
+        let hives = vec![hive("h1", 20.0, 0.8, 1500.0)];
+        let tasks: Vec<HumanTask> = [
+            HumanTaskKind::FarmingSprayReduction,
+            HumanTaskKind::PlantWildflowers,
+            HumanTaskKind::AdjustIrrigation,
+            HumanTaskKind::DimLights,
+            HumanTaskKind::ReduceNoise,
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(i, kind)| HumanTask {
+            id: format!("t{}", i),
+            kind,
+            eco_reward_hint: 1.0,
+        })
+        .collect();
+        assert!(validate_task_adjustments(&tasks, &hives).is_empty());
+
+        // A mapping that flips ReduceNoise to add noise must be reported.
+        let broken = |task: &HumanTask, hive: &HiveEnvelope| {
+            let mut adj = task_to_adjustment(task, hive);
+            if matches!(task.kind, HumanTaskKind::ReduceNoise) {
+                adj.delta_noise_db = 10.0;
+            }
+            adj
+        };
+        let violations = validate_adjustments_with(&tasks, &hives, broken);
+        assert_eq!(
+            violations,
+            vec![("adj-h1-t4".to_string(), HiveGuardError::IncreasesLightOrNoise)]
+        );
+    }
+
+    #[test]
+    fn test_acceptance_probability_matches_known_fraction() {
+        let task = HumanTask {
+            id: "dim".into(),
+            kind: HumanTaskKind::DimLights,
+            eco_reward_hint: 1.0,
+        };
+        // DimLights leaves temperature unchanged, so the overheated sample rejects.
+        let mut overheated = hive("h", 20.0, 0.8, 1500.0);
+        overheated.hive_temperature_c = 37.5;
+        let samples = vec![
+            hive("h", 20.0, 0.8, 1500.0),
+            hive("h", 45.0, 0.6, 1200.0),
+            hive("h", 60.0, 0.4, 1100.0),
+            overheated,
+        ];
+
+        assert_eq!(acceptance_probability(&task, &samples), 0.75);
+        assert_eq!(acceptance_probability(&task, &[]), 0.0);
+    }
+
+    #[test]
+    fn test_routing_reward_sensitivity_flags_borderline_task() {
+        let hives = vec![hive("toxic", 55.0, 0.8, 1500.0), hive("sparse", 20.0, 0.46, 1500.0)];
+        let task = |id: &str, kind, hint| HumanTask {
+            id: id.into(),
+            kind,
+            eco_reward_hint: hint,
+        };
+        let tasks = vec![
+            task("spray", HumanTaskKind::FarmingSprayReduction, 5.0),
+            task("flowers", HumanTaskKind::PlantWildflowers, 0.82),
+            task("lights", HumanTaskKind::DimLights, 0.8),
+        ];
+
+        // Baseline: spray relieves the toxic hive, flowers then go to the sparse one and
+        // lights land back on toxic. Nudging lights above flowers sends them to sparse.
+        let outcomes = scheduled_outcomes(&tasks, &hives);
+        assert_eq!(outcomes[2].as_deref(), Some("toxic"));
+
+        let sensitivity = routing_reward_sensitivity(&tasks, &hives, 0.05);
+        assert_eq!(sensitivity[0], ("spray".to_string(), false));
+        assert_eq!(sensitivity[2], ("lights".to_string(), true));
+    }
+
+    #[test]
+    fn test_interventions_to_target_counts_flower_plantings() {
+        let hives = vec![hive("toxic", 55.0, 0.8, 1500.0), hive("sparse", 20.0, 0.46, 1500.0)];
+        let task = |kind| HumanTask {
+            id: "t".into(),
+            kind,
+            eco_reward_hint: 1.0,
+        };
+        let flowers = task(HumanTaskKind::PlantWildflowers);
+
+        // Both hives start at 60; each planting adds 10 to one hive, so a mean of 80 takes 4.
+        assert_eq!(interventions_to_target(&hives, &flowers, 80.0), Some(4));
+        assert_eq!(interventions_to_target(&hives, &flowers, 60.0), Some(0));
+        // DimLights leaves temperature unchanged, so an overheated hive never accepts it.
+        let mut overheated = hive("hot", 20.0, 0.8, 1500.0);
+        overheated.hive_temperature_c = 37.5;
+        let dim = task(HumanTaskKind::DimLights);
+        assert_eq!(interventions_to_target(&[overheated], &dim, 61.0), None);
+        assert_eq!(interventions_to_target(&[], &flowers, 61.0), None);
+
+        // Soft forager overload docks 100 * 0.2 = 20 points: two plantings restore 60.
+        let mut overworked = hive("busy", 20.0, 0.8, 1500.0);
+        overworked.safe_forager_load_max = Some(0.5);
+        overworked.forager_load_policy = hive_guard::ForagerLoadPolicy::SoftPenalty;
+        assert_eq!(interventions_to_target(&[overworked], &flowers, 60.0), Some(2));
+    }
+
+    #[test]
+    fn test_scheduler_consumes_backlog_and_improves_bands() {
+        let hives = vec![
+            hive("toxic", 70.0, 0.8, 1500.0),
+            hive("sparse", 20.0, 0.46, 1500.0),
+            hive("fine", 20.0, 0.8, 1500.0),
+        ];
+        let task = |id: &str, kind, hint| HumanTask {
+            id: id.into(),
+            kind,
+            eco_reward_hint: hint,
+        };
+        let backlog = vec![
+            task("irrigate", HumanTaskKind::AdjustIrrigation, 0.5),
+            task("spray", HumanTaskKind::FarmingSprayReduction, 0.9),
+            task("flowers", HumanTaskKind::PlantWildflowers, 0.8),
+        ];
+        let at_risk = |s: &Scheduler| {
+            s.hives
+                .iter()
+                .filter(|h| h.evaluate_band() != EcoBand::Safe)
+                .count()
+        };
+
+        let mut scheduler = Scheduler::new(hives, backlog, 24.0, 6.0);
+        assert_eq!(at_risk(&scheduler), 2);
+
+        let first = scheduler.step().unwrap();
+        assert_eq!((first.task.id.as_str(), first.hive_id.as_str()), ("spray", "toxic"));
+        assert_eq!(at_risk(&scheduler), 1);
+
+        let second = scheduler.step().unwrap();
+        assert_eq!((second.task.id.as_str(), second.hive_id.as_str()), ("flowers", "sparse"));
+        assert_eq!(at_risk(&scheduler), 0);
+
+        assert_eq!(scheduler.step().unwrap().task.id, "irrigate");
+        assert!(scheduler.backlog.is_empty());
+        assert_eq!(scheduler.elapsed_hours, 18.0);
+        assert_eq!(scheduler.ledger.events().len(), 3);
+        assert!(scheduler.step().is_none());
+    }
+
+    #[test]
+    fn test_scheduler_decays_species_toxins_until_recovery() {
+        // Ambient toxin is within limits; only the species reading is over its cap.
+        let mut dosed = hive("dosed", 20.0, 0.8, 1500.0);
+        dosed.toxins = vec![("neonicotinoid".into(), 12.0)];
+        dosed
+            .safe_toxin_ppb_max_by_species
+            .insert("neonicotinoid".into(), 4.0);
+        dosed.eco_band = dosed.evaluate_band();
+        assert_ne!(dosed.eco_band, EcoBand::Safe);
+
+        let mut scheduler = Scheduler::new(vec![dosed], Vec::new(), 6.0, 6.0);
+        assert!(scheduler.step().is_none());
+        assert_eq!(scheduler.hives[0].toxins[0].1, 6.0);
+        assert_ne!(scheduler.hives[0].eco_band, EcoBand::Safe);
+
+        assert!(scheduler.step().is_none());
+        assert_eq!(scheduler.hives[0].toxins[0].1, 3.0);
+        assert_eq!(scheduler.hives[0].ambient_toxin_ppb, 5.0);
+        assert_eq!(scheduler.hives[0].eco_band, EcoBand::Safe);
+    }
+}
@@ -1,34 +1,11 @@
+use std::collections::HashMap;
+
 use anyhow::Result;
 use chrono::Utc;
-use rand::seq::SliceRandom;
-use rand::thread_rng;
 
+use beecorridor_router::{route_tasks_through_corridors, HumanTask, HumanTaskKind};
 use ecosocialbee_core::{EcoImpactScore, HabitatStabilityIndex, HeatRiskIndex, HumanEcoProxy, ToxinLoadIndex};
-use hive_guard::{classify_risk, EcoBand, HiveEnvelope, HiveInnerLedger, HiveSystemAdjustment, RiskEnvelope};
-
-#[derive(Debug, Clone)]
-pub enum HumanTaskKind {
-    FarmingSprayReduction,
-    PlantWildflowers,
-    AdjustIrrigation,
-    DimLights,
-    ReduceNoise,
-}
-
-#[derive(Debug, Clone)]
-pub struct HumanTask {
-    pub id: String,
-    pub kind: HumanTaskKind,
-    pub eco_reward_hint: f32,
-}
-
-#[derive(Debug, Clone)]
-pub struct RoutedTask {
-    pub task: HumanTask,
-    pub hive_id: String,
-    pub accepted: bool,
-    pub reason: String,
-}
+use hive_guard::{EcoBand, ForagerLoadPolicy, HiveEnvelope, DEFAULT_SHADE_COOLING_COEFF_C};
 
 fn sample_hives() -> Vec<HiveEnvelope> {
     vec![
@@ -49,6 +26,12 @@ fn sample_hives() -> Vec<HiveEnvelope> {
             safe_toxin_ppb_max: 50.0,
             safe_forage_diversity_index_min: 0.5,
             safe_forage_radius_m_min: 1000.0,
+            recovery_eco_threshold: None,
+            toxins: Vec::new(),
+            safe_toxin_ppb_max_by_species: HashMap::new(),
+            shade_cooling_coeff_c: DEFAULT_SHADE_COOLING_COEFF_C,
+            safe_forager_load_max: None,
+            forager_load_policy: ForagerLoadPolicy::HardBand,
         },
         HiveEnvelope {
             hive_id: "hive-beta".into(),
@@ -67,148 +50,16 @@ fn sample_hives() -> Vec<HiveEnvelope> {
             safe_toxin_ppb_max: 50.0,
             safe_forage_diversity_index_min: 0.5,
             safe_forage_radius_m_min: 1000.0,
+            recovery_eco_threshold: None,
+            toxins: Vec::new(),
+            safe_toxin_ppb_max_by_species: HashMap::new(),
+            shade_cooling_coeff_c: DEFAULT_SHADE_COOLING_COEFF_C,
+            safe_forager_load_max: None,
+            forager_load_policy: ForagerLoadPolicy::HardBand,
         },
     ]
 }
 
-fn task_to_adjustment(task: &HumanTask, hive: &HiveEnvelope) -> HiveSystemAdjustment {
-    let now = Utc::now();
-    match task.kind {
-        HumanTaskKind::FarmingSprayReduction => HiveSystemAdjustment {
-            id: format!("adj-{}-{}", hive.hive_id, task.id),
-            timestamp: now,
-            hive_id: hive.hive_id.clone(),
-            delta_pesticide_exposure_ppb: -10.0,
-            delta_shade_fraction: 0.0,
-            delta_water_availability_index: 0.0,
-            delta_forage_radius_m: 0.0,
-            delta_forage_diversity_index: 0.05,
-            delta_artificial_light_nits: 0.0,
-            delta_noise_db: 0.0,
-            delta_eco_impact_score_corridor: 5.0,
-        },
-        HumanTaskKind::PlantWildflowers => HiveSystemAdjustment {
-            id: format!("adj-{}-{}", hive.hive_id, task.id),
-            timestamp: now,
-            hive_id: hive.hive_id.clone(),
-            delta_pesticide_exposure_ppb: 0.0,
-            delta_shade_fraction: 0.0,
-            delta_water_availability_index: 0.1,
-            delta_forage_radius_m: 200.0,
-            delta_forage_diversity_index: 0.15,
-            delta_artificial_light_nits: 0.0,
-            delta_noise_db: 0.0,
-            delta_eco_impact_score_corridor: 10.0,
-        },
-        HumanTaskKind::AdjustIrrigation => HiveSystemAdjustment {
-            id: format!("adj-{}-{}", hive.hive_id, task.id),
-            timestamp: now,
-            hive_id: hive.hive_id.clone(),
-            delta_pesticide_exposure_ppb: 0.0,
-            delta_shade_fraction: 0.0,
-            delta_water_availability_index: 0.1,
-            delta_forage_radius_m: 0.0,
-            delta_forage_diversity_index: 0.02,
-            delta_artificial_light_nits: 0.0,
-            delta_noise_db: 0.0,
-            delta_eco_impact_score_corridor: 2.0,
-        },
-        HumanTaskKind::DimLights => HiveSystemAdjustment {
-            id: format!("adj-{}-{}", hive.hive_id, task.id),
-            timestamp: now,
-            hive_id: hive.hive_id.clone(),
-            delta_pesticide_exposure_ppb: 0.0,
-            delta_shade_fraction: 0.0,
-            delta_water_availability_index: 0.0,
-            delta_forage_radius_m: 0.0,
-            delta_forage_diversity_index: 0.0,
-            delta_artificial_light_nits: -50.0,
-            delta_noise_db: 0.0,
-            delta_eco_impact_score_corridor: 1.0,
-        },
-        HumanTaskKind::ReduceNoise => HiveSystemAdjustment {
-            id: format!("adj-{}-{}", hive.hive_id, task.id),
-            timestamp: now,
-            hive_id: hive.hive_id.clone(),
-            delta_pesticide_exposure_ppb: 0.0,
-            delta_shade_fraction: 0.0,
-            delta_water_availability_index: 0.0,
-            delta_forage_radius_m: 0.0,
-            delta_forage_diversity_index: 0.0,
-            delta_artificial_light_nits: 0.0,
-            delta_noise_db: -10.0,
-            delta_eco_impact_score_corridor: 1.0,
-        },
-    }
-}
-
-fn route_tasks_through_corridors(
-    tasks: &[HumanTask],
-    hives: &mut [HiveEnvelope],
-) -> Vec<RoutedTask> {
-    let mut rng = thread_rng();
-    let mut ledger = HiveInnerLedger::new();
-    let mut results = Vec::new();
-
-    for task in tasks {
-        // Prefer hives in worse risk bands to receive protective actions first.
-        let mut candidates = hives.to_vec();
-        candidates.sort_by_key(|h| match classify_risk(h) {
-            RiskEnvelope::Critical => 0,
-            RiskEnvelope::Warning => 1,
-            RiskEnvelope::Safe => 2,
-        });
-
-        let mut routed = None;
-
-        for hive in candidates.iter_mut() {
-            let adj = task_to_adjustment(task, hive);
-            match ledger.apply_adjustment(hive.clone(), adj) {
-                Ok(new_env) => {
-                    // Update original hive entry.
-                    if let Some(orig) = hives.iter_mut().find(|h| h.hive_id == new_env.hive_id) {
-                        *orig = new_env;
-                    }
-
-                    routed = Some(RoutedTask {
-                        task: task.clone(),
-                        hive_id: hive.hive_id.clone(),
-                        accepted: true,
-                        reason: "Adjustment satisfies all hive safety invariants".into(),
-                    });
-                    break;
-                }
-                Err(err) => {
-                    // Try next hive; keep explanation for debug.
-                    routed.get_or_insert(RoutedTask {
-                        task: task.clone(),
-                        hive_id: hive.hive_id.clone(),
-                        accepted: false,
-                        reason: format!("Rejected by hive ledger: {}", err),
-                    });
-                }
-            }
-        }
-
-        if let Some(r) = routed {
-            results.push(r);
-        } else {
-            let hive_id = hives
-                .choose(&mut rng)
-                .map(|h| h.hive_id.clone())
-                .unwrap_or_else(|| "none".into());
-            results.push(RoutedTask {
-                task: task.clone(),
-                hive_id,
-                accepted: false,
-                reason: "No hive could accept adjustment under safety invariants".into(),
-            });
-        }
-    }
-
-    results
-}
-
 fn sample_tasks() -> Vec<HumanTask> {
     vec![
         HumanTask {
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -60,6 +62,9 @@ impl EcoImpactScore {
 pub struct HumanEcoProxy {
     pub actor_human_id: String,
     pub timestamp: DateTime<Utc>,
+    /// Geographic region label for program rollups; empty when unassigned.
+    #[serde(default)]
+    pub region: String,
     pub device_hour_displacement: f32,
     pub estimated_kwh_saved: f32,
     pub estimated_emissions_kg_co2e_avoided: f32,
@@ -77,6 +82,7 @@ pub struct HumanEcoProxy {
 
 impl HumanEcoProxy {
     /// Construct a proxy with monotone eco impact: caller must ensure new_score >= old_score if chaining.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         actor_human_id: impl Into<String>,
         timestamp: DateTime<Utc>,
@@ -100,6 +106,7 @@ impl HumanEcoProxy {
         Self {
             actor_human_id: actor_human_id.into(),
             timestamp,
+            region: String::new(),
             device_hour_displacement,
             estimated_kwh_saved,
             estimated_emissions_kg_co2e_avoided,
@@ -114,4 +121,123 @@ impl HumanEcoProxy {
             human_only_tolerance_index: None,
         }
     }
+
+    pub fn with_region(mut self, region: impl Into<String>) -> Self {
+        self.region = region.into();
+        self
+    }
+}
+
+/// Rollup of human eco-proxy metrics across a campaign or region.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CampaignSummary {
+    pub proxy_count: usize,
+    pub total_kwh_saved: f32,
+    pub total_emissions_kg_co2e_avoided: f32,
+    pub total_pollinator_habitat_area_m2: f32,
+    pub total_reduced_spray_events: u32,
+    pub total_reduced_spray_volume_l: f32,
+    pub mean_eco_impact_score: f32,
+}
+
+impl CampaignSummary {
+    fn add(&mut self, p: &HumanEcoProxy) {
+        let n = self.proxy_count as f32;
+        self.mean_eco_impact_score =
+            (self.mean_eco_impact_score * n + p.eco_impact_score.0) / (n + 1.0);
+        self.proxy_count += 1;
+        self.total_kwh_saved += p.estimated_kwh_saved;
+        self.total_emissions_kg_co2e_avoided += p.estimated_emissions_kg_co2e_avoided;
+        self.total_pollinator_habitat_area_m2 += p.pollinator_habitat_area_m2;
+        self.total_reduced_spray_events += p.reduced_spray_events_count;
+        self.total_reduced_spray_volume_l += p.reduced_spray_volume_l;
+    }
+}
+
+/// Global rollup of all proxies.
+pub fn aggregate_proxies(proxies: &[HumanEcoProxy]) -> CampaignSummary {
+    let mut summary = CampaignSummary::default();
+    for p in proxies {
+        summary.add(p);
+    }
+    summary
+}
+
+/// Rollup of proxies keyed by their `region`.
+pub fn aggregate_by_region(proxies: &[HumanEcoProxy]) -> HashMap<String, CampaignSummary> {
+    let mut by_region: HashMap<String, CampaignSummary> = HashMap::new();
+    for p in proxies {
+        by_region.entry(p.region.clone()).or_default().add(p);
+    }
+    by_region
+}
+
+/// Total avoided emissions (kg CO2e) across a campaign of proxies.
+///
+/// Proxies that map to the same `dedup_key` are treated as one underlying
+/// action and contribute only their largest reported value, so re-logged or
+/// co-reported actions are not double-counted.
+pub fn total_avoided_emissions(
+    proxies: &[HumanEcoProxy],
+    dedup_key: impl Fn(&HumanEcoProxy) -> String,
+) -> f32 {
+    let mut by_key: HashMap<String, f32> = HashMap::new();
+    for p in proxies {
+        let entry = by_key
+            .entry(dedup_key(p))
+            .or_insert(p.estimated_emissions_kg_co2e_avoided);
+        *entry = entry.max(p.estimated_emissions_kg_co2e_avoided);
+    }
+    by_key.values().sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proxy(actor: &str, emissions_kg: f32) -> HumanEcoProxy {
+        HumanEcoProxy::new(
+            actor,
+            Utc::now(),
+            1.0,
+            0.5,
+            emissions_kg,
+            10.0,
+            0.8,
+            1,
+            2.0,
+            1.0,
+            1.0,
+        )
+    }
+
+    #[test]
+    fn test_total_avoided_emissions_collapses_shared_keys() {
+        let proxies = vec![
+            proxy("human-a", 2.0),
+            proxy("human-a", 3.0),
+            proxy("human-b", 1.5),
+        ];
+        let total = total_avoided_emissions(&proxies, |p| p.actor_human_id.clone());
+        assert!((total - 4.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_aggregate_by_region_separates_rollups() {
+        let proxies = vec![
+            proxy("human-a", 2.0).with_region("phoenix-north"),
+            proxy("human-b", 3.0).with_region("phoenix-north"),
+            proxy("human-c", 1.5).with_region("tempe"),
+        ];
+        let by_region = aggregate_by_region(&proxies);
+
+        assert_eq!(by_region.len(), 2);
+        let north = &by_region["phoenix-north"];
+        assert_eq!(north.proxy_count, 2);
+        assert!((north.total_emissions_kg_co2e_avoided - 5.0).abs() < 1e-6);
+        let tempe = &by_region["tempe"];
+        assert_eq!(tempe.proxy_count, 1);
+        assert!((tempe.total_emissions_kg_co2e_avoided - 1.5).abs() < 1e-6);
+        assert_eq!(aggregate_proxies(&proxies).proxy_count, 3);
+    }
 }
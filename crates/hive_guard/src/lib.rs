@@ -1,17 +1,38 @@
-use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// ECO_BAND represents the risk envelope outcome for a hive.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+/// Ordered by severity, `Safe < Warning < Recovering < Critical`: higher means worse.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub enum EcoBand {
     Safe,
     Warning,
+    /// One metric still out of band, but eco impact is above the envelope's
+    /// `recovery_eco_threshold`: still bad, trending up.
+    Recovering,
     Critical,
 }
 
+impl EcoBand {
+    /// Stable code fed to `HiveEnvelope::content_hash`. Independent of declaration
+    /// (severity) order: each variant keeps the code it was introduced with, so
+    /// adding a band never changes existing hashes.
+    fn hash_code(self) -> u8 {
+        match self {
+            EcoBand::Safe => 0,
+            EcoBand::Warning => 1,
+            EcoBand::Critical => 2,
+            EcoBand::Recovering => 3,
+        }
+    }
+}
+
 /// HiveEnvelope encodes bee-centered metrics only: no human fields.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct HiveEnvelope {
     pub hive_id: String,
     pub brood_frames: u32,           // BROOD
@@ -29,6 +50,48 @@ pub struct HiveEnvelope {
     pub safe_toxin_ppb_max: f32,
     pub safe_forage_diversity_index_min: f32,
     pub safe_forage_radius_m_min: f32,
+    /// Eco-impact score above which a single-breach hive is `Recovering` rather
+    /// than `Warning`. `None` disables the Recovering band.
+    #[serde(default)]
+    pub recovery_eco_threshold: Option<f32>,
+    /// Per-species ambient levels (species, ppb), e.g. neonicotinoids or fungicides.
+    /// `ambient_toxin_ppb` remains the aggregate species.
+    #[serde(default)]
+    pub toxins: Vec<(String, f32)>,
+    /// Safe maximum per species; species without an entry use `safe_toxin_ppb_max`.
+    #[serde(default)]
+    pub safe_toxin_ppb_max_by_species: HashMap<String, f32>,
+    /// Maximum cooling (C) from full shade; lower for hives with high thermal mass.
+    #[serde(default = "default_shade_cooling_coeff_c")]
+    pub shade_cooling_coeff_c: f32,
+    /// Sustainable forager load (0-1); `None` leaves forager load unchecked.
+    #[serde(default)]
+    pub safe_forager_load_max: Option<f32>,
+    /// How a load above `safe_forager_load_max` is treated by `evaluate_band`.
+    #[serde(default)]
+    pub forager_load_policy: ForagerLoadPolicy,
+}
+
+/// Treatment of forager overwork, which unlike toxins or heat is recoverable.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ForagerLoadPolicy {
+    /// Overload counts as a band breach like temperature, toxin or forage.
+    #[default]
+    HardBand,
+    /// Overload leaves the band alone but deducts `FORAGER_OVERLOAD_PENALTY`
+    /// per unit of excess load from the eco impact score.
+    SoftPenalty,
+}
+
+/// Eco impact points deducted per unit of forager load above the safe maximum
+/// under `ForagerLoadPolicy::SoftPenalty`.
+pub const FORAGER_OVERLOAD_PENALTY: f32 = 100.0;
+
+/// Default `shade_cooling_coeff_c`: full shade cools a hive by up to 5 C.
+pub const DEFAULT_SHADE_COOLING_COEFF_C: f32 = 5.0;
+
+fn default_shade_cooling_coeff_c() -> f32 {
+    DEFAULT_SHADE_COOLING_COEFF_C
 }
 
 impl HiveEnvelope {
@@ -36,20 +99,236 @@ impl HiveEnvelope {
         let temp_ok =
             self.hive_temperature_c >= self.safe_temperature_c_min
                 && self.hive_temperature_c <= self.safe_temperature_c_max;
-        let toxin_ok = self.ambient_toxin_ppb <= self.safe_toxin_ppb_max;
+        let toxin_ok = self.ambient_toxin_ppb <= self.safe_toxin_ppb_max
+            && self.toxins.iter().all(|(species, ppb)| *ppb <= self.safe_toxin_max_for(species));
         let forage_ok = self.forage_diversity_index >= self.safe_forage_diversity_index_min
             && self.forage_radius_m >= self.safe_forage_radius_m_min;
 
+        let load_ok = self.forager_load_policy == ForagerLoadPolicy::SoftPenalty
+            || !self.forager_overloaded();
+
+        let breaches = [temp_ok, toxin_ok, forage_ok, load_ok].iter().filter(|ok| !**ok).count();
+        let improving = self
+            .recovery_eco_threshold
+            .is_some_and(|t| self.effective_eco_impact_score() > t);
+
         match (temp_ok, toxin_ok, forage_ok) {
-            (true, true, true) => EcoBand::Safe,
+            _ if breaches == 0 => EcoBand::Safe,
             (false, false, false) => EcoBand::Critical,
+            _ if breaches == 1 && improving => EcoBand::Recovering,
             _ => EcoBand::Warning,
         }
     }
+
+    /// Forager load above `safe_forager_load_max`, if one is set.
+    pub fn forager_overloaded(&self) -> bool {
+        self.safe_forager_load_max.is_some_and(|max| self.forager_load > max)
+    }
+
+    /// `eco_impact_score_corridor` less the soft forager-overload penalty, floored
+    /// at 0. Equal to the raw score under `ForagerLoadPolicy::HardBand`.
+    pub fn effective_eco_impact_score(&self) -> f32 {
+        match (self.forager_load_policy, self.safe_forager_load_max) {
+            (ForagerLoadPolicy::SoftPenalty, Some(max)) if self.forager_load > max => {
+                let excess = self.forager_load - max;
+                (self.eco_impact_score_corridor - FORAGER_OVERLOAD_PENALTY * excess).max(0.0)
+            }
+            _ => self.eco_impact_score_corridor,
+        }
+    }
+
+    pub fn with_shade_cooling_coeff_c(mut self, coeff_c: f32) -> Self {
+        self.shade_cooling_coeff_c = coeff_c;
+        self
+    }
+
+    /// Safe maximum for one toxin species, falling back to the aggregate limit.
+    pub fn safe_toxin_max_for(&self, species: &str) -> f32 {
+        self.safe_toxin_ppb_max_by_species
+            .get(species)
+            .copied()
+            .unwrap_or(self.safe_toxin_ppb_max)
+    }
+
+    /// Graded alternative to `evaluate_band`: a 0-1 stress score averaging the
+    /// temperature, toxin (worst species) and forage distances from their safe bounds (each
+    /// normalized as in `survival_estimate`'s overshoot and capped at 1; forage is
+    /// the worse of diversity and radius). Scores below 0.33 are Safe, below 0.66
+    /// Warning, otherwise Critical. Averaging never hides a breach: any component
+    /// past its bound floors the band at Warning, and one at or beyond a full
+    /// bound's width (saturated) floors it at Critical.
+    pub fn evaluate_band_weighted(&self) -> (EcoBand, f32) {
+        let (temp, toxin, diversity, radius) = safe_band_distances(self);
+        let forage = diversity.max(radius);
+        let worst = temp.max(toxin).max(forage);
+        let score = (temp.min(1.0) + toxin.min(1.0) + forage.min(1.0)) / 3.0;
+        let band = if score >= 0.66 || worst >= 1.0 {
+            EcoBand::Critical
+        } else if score >= 0.33 || worst > 0.0 {
+            EcoBand::Warning
+        } else {
+            EcoBand::Safe
+        };
+        (band, score)
+    }
+
+    /// Check field ranges that `evaluate_band` silently relies on: diversity
+    /// indices in [0, 1], non-negative radii and stores, and a non-inverted safe
+    /// temperature band. NaN fails every check.
+    pub fn validate(&self) -> Result<(), HiveEnvelopeError> {
+        for (field, value) in [
+            ("forage_diversity_index", self.forage_diversity_index),
+            ("safe_forage_diversity_index_min", self.safe_forage_diversity_index_min),
+        ] {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(HiveEnvelopeError::DiversityOutOfRange { field: field.into(), value });
+            }
+        }
+        for (field, value) in [
+            ("forage_radius_m", self.forage_radius_m),
+            ("safe_forage_radius_m_min", self.safe_forage_radius_m_min),
+            ("nectar_kg", self.nectar_kg),
+            ("pollen_kg", self.pollen_kg),
+        ] {
+            if value.is_nan() || value < 0.0 {
+                return Err(HiveEnvelopeError::NegativeQuantity { field: field.into(), value });
+            }
+        }
+        for (species, ppb) in &self.toxins {
+            let value = *ppb;
+            if value.is_nan() || value < 0.0 {
+                let field = format!("toxins[{}]", species);
+                return Err(HiveEnvelopeError::NegativeQuantity { field, value });
+            }
+        }
+        let (min, max) = (self.safe_temperature_c_min, self.safe_temperature_c_max);
+        if min.is_nan() || max.is_nan() || min > max {
+            return Err(HiveEnvelopeError::InvertedTemperatureBand { min, max });
+        }
+        Ok(())
+    }
+
+    /// Names every non-finite (NaN or infinite) numeric field, e.g. from a faulty
+    /// sensor. Empty when all readings and limits are finite.
+    pub fn sanitize(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let optional = [
+            ("recovery_eco_threshold", self.recovery_eco_threshold),
+            ("safe_forager_load_max", self.safe_forager_load_max),
+        ];
+        for (field, value) in [
+            ("nectar_kg", self.nectar_kg),
+            ("pollen_kg", self.pollen_kg),
+            ("hive_temperature_c", self.hive_temperature_c),
+            ("forager_load", self.forager_load),
+            ("ambient_toxin_ppb", self.ambient_toxin_ppb),
+            ("forage_diversity_index", self.forage_diversity_index),
+            ("forage_radius_m", self.forage_radius_m),
+            ("eco_impact_score_corridor", self.eco_impact_score_corridor),
+            ("safe_temperature_c_min", self.safe_temperature_c_min),
+            ("safe_temperature_c_max", self.safe_temperature_c_max),
+            ("safe_toxin_ppb_max", self.safe_toxin_ppb_max),
+            ("safe_forage_diversity_index_min", self.safe_forage_diversity_index_min),
+            ("safe_forage_radius_m_min", self.safe_forage_radius_m_min),
+            ("shade_cooling_coeff_c", self.shade_cooling_coeff_c),
+        ]
+        .into_iter()
+        .chain(optional.into_iter().filter_map(|(f, v)| v.map(|v| (f, v))))
+        {
+            if !value.is_finite() {
+                problems.push(format!("{}: non-finite value {}", field, value));
+            }
+        }
+        for (species, ppb) in &self.toxins {
+            if !ppb.is_finite() {
+                problems.push(format!("toxins[{}]: non-finite value {}", species, ppb));
+            }
+        }
+        let mut limits: Vec<_> = self.safe_toxin_ppb_max_by_species.iter().collect();
+        limits.sort_by(|a, b| a.0.cmp(b.0));
+        for (species, max) in limits {
+            if !max.is_finite() {
+                problems.push(format!(
+                    "safe_toxin_ppb_max_by_species[{}]: non-finite value {}",
+                    species, max
+                ));
+            }
+        }
+        problems
+    }
+
+    /// Stable 64-bit FNV-1a hash of the envelope for deduplication and caching.
+    /// Floats are quantized to `CONTENT_HASH_PRECISION` (0.001 in the field's unit)
+    /// before hashing; all NaNs hash alike. Stable across runs and platforms.
+    pub fn content_hash(&self) -> u64 {
+        let mut h = FNV_OFFSET;
+        let mut feed = |bytes: &[u8]| h = fnv1a(h, bytes);
+        let quantize = |x: f32| -> i64 {
+            if x.is_nan() {
+                i64::MIN
+            } else {
+                (f64::from(x) / CONTENT_HASH_PRECISION).round() as i64
+            }
+        };
+
+        feed(self.hive_id.as_bytes());
+        feed(&[0xff]);
+        feed(&self.brood_frames.to_le_bytes());
+        feed(&[self.eco_band.hash_code(), self.forager_load_policy as u8]);
+        for x in [
+            self.nectar_kg,
+            self.pollen_kg,
+            self.hive_temperature_c,
+            self.forager_load,
+            self.ambient_toxin_ppb,
+            self.forage_diversity_index,
+            self.forage_radius_m,
+            self.eco_impact_score_corridor,
+            self.safe_temperature_c_min,
+            self.safe_temperature_c_max,
+            self.safe_toxin_ppb_max,
+            self.safe_forage_diversity_index_min,
+            self.safe_forage_radius_m_min,
+            self.recovery_eco_threshold.unwrap_or(f32::NAN),
+            self.shade_cooling_coeff_c,
+            self.safe_forager_load_max.unwrap_or(f32::NAN),
+        ] {
+            feed(&quantize(x).to_le_bytes());
+        }
+        for (species, ppb) in &self.toxins {
+            feed(species.as_bytes());
+            feed(&[0xff]);
+            feed(&quantize(*ppb).to_le_bytes());
+        }
+        let mut limits: Vec<_> = self.safe_toxin_ppb_max_by_species.iter().collect();
+        limits.sort_by(|a, b| a.0.cmp(b.0));
+        for (species, max) in limits {
+            feed(species.as_bytes());
+            feed(&[0xfe]);
+            feed(&quantize(*max).to_le_bytes());
+        }
+        h
+    }
+}
+
+/// Quantization step applied to float fields by `HiveEnvelope::content_hash`.
+pub const CONTENT_HASH_PRECISION: f64 = 1.0e-3;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Continue an FNV-1a hash `h` over `bytes`.
+fn fnv1a(mut h: u64, bytes: &[u8]) -> u64 {
+    for b in bytes {
+        h ^= u64::from(*b);
+        h = h.wrapping_mul(FNV_PRIME);
+    }
+    h
 }
 
 /// HiveSystemAdjustment describes environmental changes only, no bee-body fields.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Timestamps serialize as RFC3339 strings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct HiveSystemAdjustment {
     pub id: String,
     pub timestamp: DateTime<Utc>,
@@ -66,21 +345,111 @@ pub struct HiveSystemAdjustment {
     pub delta_eco_impact_score_corridor: f32,
 }
 
+/// Deltas smaller than this in magnitude count as zero for `HiveGuardError::NoEffect`.
+pub const NO_EFFECT_EPSILON: f32 = 1.0e-6;
+
+impl HiveSystemAdjustment {
+    /// True when every environmental and eco delta is within `NO_EFFECT_EPSILON` of zero.
+    pub fn is_no_op(&self) -> bool {
+        [
+            self.delta_pesticide_exposure_ppb,
+            self.delta_shade_fraction,
+            self.delta_water_availability_index,
+            self.delta_forage_radius_m,
+            self.delta_forage_diversity_index,
+            self.delta_artificial_light_nits,
+            self.delta_noise_db,
+            self.delta_eco_impact_score_corridor,
+        ]
+        .iter()
+        .all(|d| d.abs() < NO_EFFECT_EPSILON)
+    }
+
+    /// Violations of the state-independent sign constraints: pesticide, light and
+    /// noise must not increase, forage radius must not shrink, and the eco-impact
+    /// delta must be non-negative. Temperature and radius floors depend on the
+    /// hive and are left to `HiveInnerLedger::check_adjustment`.
+    pub fn sign_violations(&self) -> Vec<HiveGuardError> {
+        let mut violations = Vec::new();
+        if self.delta_pesticide_exposure_ppb > 0.0 {
+            violations.push(HiveGuardError::IncreasesPesticideExposure);
+        }
+        if self.delta_forage_radius_m < 0.0 {
+            violations.push(HiveGuardError::ReducesForageRadius);
+        }
+        if self.delta_artificial_light_nits > 0.0 || self.delta_noise_db > 0.0 {
+            violations.push(HiveGuardError::IncreasesLightOrNoise);
+        }
+        if self.delta_eco_impact_score_corridor < 0.0 {
+            violations.push(HiveGuardError::DecreasesEcoImpactScore);
+        }
+        violations
+    }
+}
+
 /// Inner ledger event for traceability.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct HiveLedgerEvent {
     pub adjustment: HiveSystemAdjustment,
     pub pre_envelope: HiveEnvelope,
     pub post_envelope: HiveEnvelope,
 }
 
+/// Result of `apply_adjustment_detailed`: the new envelope plus what changed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HiveAdjustmentOutcome {
+    pub post: HiveEnvelope,
+    pub delta_temp_c: f32,
+    pub delta_forage_radius_m: f32,
+    pub delta_eco_impact: f32,
+    /// `Some((old, new))` only when `evaluate_band` transitions.
+    pub band_changed: Option<(EcoBand, EcoBand)>,
+}
+
+/// Rollup of accepted adjustments over a time window, from `HiveInnerLedger::summarize`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LedgerSummary {
+    pub event_count: usize,
+    pub net_delta_eco_impact_score_corridor: f32,
+    pub net_delta_forage_radius_m: f32,
+    /// Lowest post-adjustment hive temperature in the window; `None` if empty.
+    pub min_projected_temp_c: Option<f32>,
+    /// Highest post-adjustment hive temperature in the window; `None` if empty.
+    pub max_projected_temp_c: Option<f32>,
+}
+
 /// HiveInnerLedger keeps a history of accepted, rights-safe adjustments.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "StoredLedger")]
 pub struct HiveInnerLedger {
     events: Vec<HiveLedgerEvent>,
+    /// Attempts denied by an invariant, recorded only via `try_apply`.
+    #[serde(default)]
+    rejected: Vec<(HiveSystemAdjustment, HiveGuardError)>,
+    /// Adjustment id -> index of its event in `events`; rebuilt on load.
+    #[serde(skip)]
+    by_id: HashMap<String, usize>,
 }
 
-#[derive(Debug, Error)]
+/// Serialized form of `HiveInnerLedger`, without the derived id index.
+#[derive(Deserialize)]
+struct StoredLedger {
+    events: Vec<HiveLedgerEvent>,
+    #[serde(default)]
+    rejected: Vec<(HiveSystemAdjustment, HiveGuardError)>,
+}
+
+impl From<StoredLedger> for HiveInnerLedger {
+    fn from(stored: StoredLedger) -> Self {
+        let mut ledger = Self { rejected: stored.rejected, ..Self::new() };
+        for event in stored.events {
+            ledger.record(event);
+        }
+        ledger
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Error, Serialize, Deserialize)]
 pub enum HiveGuardError {
     #[error("Adjustment would increase pesticide exposure")]
     IncreasesPesticideExposure,
@@ -92,12 +461,84 @@ pub enum HiveGuardError {
     IncreasesLightOrNoise,
     #[error("Adjustment would decrease eco impact score corridor")]
     DecreasesEcoImpactScore,
+    #[error("Adjustment has no effect: every delta is zero")]
+    NoEffect,
+    #[error("Invalid hive envelope: {0}")]
+    InvalidEnvelope(#[from] HiveEnvelopeError),
+    #[error("Adjustment id {0} was already applied with different contents")]
+    DuplicateAdjustment(String),
+}
+
+/// Field-range violations found by `HiveEnvelope::validate`.
+#[derive(Debug, Clone, PartialEq, Error, Serialize, Deserialize)]
+pub enum HiveEnvelopeError {
+    #[error("{field}={value} is outside the diversity index range [0, 1]")]
+    DiversityOutOfRange { field: String, value: f32 },
+    #[error("{field}={value} must be non-negative")]
+    NegativeQuantity { field: String, value: f32 },
+    #[error("safe temperature band is inverted: min {min} > max {max}")]
+    InvertedTemperatureBand { min: f32, max: f32 },
+}
+
+impl HiveGuardError {
+    /// Compact reproducer naming the field, value, and threshold that tripped this
+    /// invariant for `adj` applied to `env`.
+    pub fn reproduction_hint(&self, env: &HiveEnvelope, adj: &HiveSystemAdjustment) -> String {
+        match self {
+            HiveGuardError::IncreasesPesticideExposure => format!(
+                "delta_pesticide_exposure_ppb={:+.1} violates IncreasesPesticideExposure (must be <= 0)",
+                adj.delta_pesticide_exposure_ppb
+            ),
+            HiveGuardError::RaisesHiveTemperature => format!(
+                "hive_temperature_c={:.1} with delta_shade_fraction={:+.2}, delta_water_availability_index={:+.2} projects {:.1} > safe_temperature_c_max={:.1}, violates RaisesHiveTemperature",
+                env.hive_temperature_c,
+                adj.delta_shade_fraction,
+                adj.delta_water_availability_index,
+                env.hive_temperature_c + projected_temp_delta(env, adj),
+                env.safe_temperature_c_max
+            ),
+            HiveGuardError::ReducesForageRadius => format!(
+                "forage_radius_m={:.1} with delta_forage_radius_m={:+.1} projects {:.1} < safe_forage_radius_m_min={:.1}, violates ReducesForageRadius",
+                env.forage_radius_m,
+                adj.delta_forage_radius_m,
+                env.forage_radius_m + adj.delta_forage_radius_m,
+                env.safe_forage_radius_m_min
+            ),
+            HiveGuardError::IncreasesLightOrNoise => {
+                if adj.delta_noise_db > 0.0 {
+                    format!(
+                        "delta_noise_db={:+.1} violates IncreasesLightOrNoise (must be <= 0)",
+                        adj.delta_noise_db
+                    )
+                } else {
+                    format!(
+                        "delta_artificial_light_nits={:+.1} violates IncreasesLightOrNoise (must be <= 0)",
+                        adj.delta_artificial_light_nits
+                    )
+                }
+            }
+            HiveGuardError::DecreasesEcoImpactScore => format!(
+                "delta_eco_impact_score_corridor={:+.1} violates DecreasesEcoImpactScore (must be >= 0)",
+                adj.delta_eco_impact_score_corridor
+            ),
+            HiveGuardError::NoEffect => format!(
+                "all deltas of {} are within {:e} of zero, violates NoEffect",
+                adj.id, NO_EFFECT_EPSILON
+            ),
+            HiveGuardError::InvalidEnvelope(e) => format!("hive {}: {}", env.hive_id, e),
+            HiveGuardError::DuplicateAdjustment(id) => {
+                format!("id={} is already in the ledger, violates DuplicateAdjustment", id)
+            }
+        }
+    }
 }
 
 // Hex-stamp: 0xa1b2c3d4e5f67890
 // Knowledge-Factor: 0.93, Eco-impact: 0.90, Risk-of-harm: 0.13
 
 pub mod bands {
+    use thiserror::Error;
+
     /// Corridor bands for a single bee-relevant metric (dimensionless risk 0–1).
     #[derive(Clone, Debug)]
     pub struct CorridorBands {
@@ -109,9 +550,14 @@ pub mod bands {
         pub weight: f64,              // contribution to residual V
         pub lyap_channel: u32,        // for diagnostics
         pub mandatory: bool,          // true => no corridor, no build
+        /// Also penalize readings below `safe_low` (e.g. humidity, diversity).
+        pub two_sided: bool,
+        pub safe_low: f64,            // lower safe bound (two-sided only, <= safe)
+        pub hard_low: f64,            // lower hard limit (two-sided only, <= safe_low)
     }
 
     impl CorridorBands {
+        #[allow(clippy::too_many_arguments)]
         pub fn new(
             var_id: &'static str,
             units: &'static str,
@@ -131,13 +577,153 @@ pub mod bands {
                 weight,
                 lyap_channel,
                 mandatory,
+                two_sided: false,
+                safe_low: 0.0,
+                hard_low: 0.0,
+            }
+        }
+
+        /// Make the corridor two-sided: risk also ramps from 0 at `safe_low` to 1
+        /// at `hard_low` as the measurement falls.
+        pub fn with_low_bands(mut self, safe_low: f64, hard_low: f64) -> Self {
+            self.two_sided = true;
+            self.safe_low = safe_low;
+            self.hard_low = hard_low;
+            self
+        }
+
+        /// Like `new`, but rejects bands that would break `risk::to_risk`'s normalization.
+        #[allow(clippy::too_many_arguments)]
+        pub fn try_new(
+            var_id: &'static str,
+            units: &'static str,
+            safe: f64,
+            gold: f64,
+            hard: f64,
+            weight: f64,
+            lyap_channel: u32,
+            mandatory: bool,
+        ) -> Result<Self, BandError> {
+            let bands = Self::new(var_id, units, safe, gold, hard, weight, lyap_channel, mandatory);
+            bands.validate()?;
+            Ok(bands)
+        }
+
+        /// Check `0 <= safe <= gold <= hard`, `hard > 0` and `weight >= 0`, plus
+        /// `hard_low <= safe_low <= safe` when two-sided; NaN fails.
+        pub fn validate(&self) -> Result<(), BandError> {
+            let ordered = 0.0 <= self.safe && self.safe <= self.gold && self.gold <= self.hard;
+            if !ordered || self.hard <= 0.0 || self.hard.is_nan() {
+                return Err(BandError::Unordered {
+                    var_id: self.var_id,
+                    safe: self.safe,
+                    gold: self.gold,
+                    hard: self.hard,
+                });
+            }
+            if self.weight.is_nan() || self.weight < 0.0 {
+                return Err(BandError::NegativeWeight {
+                    var_id: self.var_id,
+                    weight: self.weight,
+                });
+            }
+            if self.two_sided && !self.low_ordered() {
+                return Err(BandError::UnorderedLow {
+                    var_id: self.var_id,
+                    hard_low: self.hard_low,
+                    safe_low: self.safe_low,
+                    safe: self.safe,
+                });
+            }
+            Ok(())
+        }
+
+        /// `hard_low <= safe_low <= safe`, with NaN unordered.
+        pub(crate) fn low_ordered(&self) -> bool {
+            self.hard_low <= self.safe_low && self.safe_low <= self.safe
+        }
+
+        /// Zone a raw measurement falls in; each zone includes its upper threshold.
+        pub fn zone(&self, measured: f64) -> BandZone {
+            if measured <= self.safe {
+                BandZone::Safe
+            } else if measured <= self.gold {
+                BandZone::Gold
+            } else if measured <= self.hard {
+                BandZone::Hard
+            } else {
+                BandZone::Over
+            }
+        }
+
+        /// `zone` extended below the band for two-sided corridors, mirroring
+        /// `risk::to_risk_two_sided`: the low side's gold point sits midway between
+        /// `safe_low` and `hard_low` (r = 0.5), and each zone includes its threshold
+        /// nearer the safe band. One-sided bands give exactly `zone`.
+        pub fn zone_two_sided(&self, measured: f64) -> BandZone {
+            if !self.two_sided || measured >= self.safe_low {
+                return self.zone(measured);
+            }
+            let gold_low = 0.5 * (self.safe_low + self.hard_low);
+            if measured >= gold_low {
+                BandZone::Gold
+            } else if measured >= self.hard_low {
+                BandZone::Hard
+            } else {
+                BandZone::Over
             }
         }
+
+        /// True when `measured` exceeds the hard limit or, on a two-sided band, falls
+        /// below `hard_low`; exactly at either hard limit is not a violation.
+        pub fn is_violation(&self, measured: f64) -> bool {
+            self.zone_two_sided(measured) == BandZone::Over
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Error)]
+    pub enum BandError {
+        #[error("{var_id}: bands must satisfy 0 <= safe <= gold <= hard with hard > 0, got {safe}/{gold}/{hard}")]
+        Unordered {
+            var_id: &'static str,
+            safe: f64,
+            gold: f64,
+            hard: f64,
+        },
+        #[error("{var_id}: weight must be non-negative, got {weight}")]
+        NegativeWeight { var_id: &'static str, weight: f64 },
+        #[error("{var_id}: low bands must satisfy hard_low <= safe_low <= safe, got {hard_low}/{safe_low}/{safe}")]
+        UnorderedLow {
+            var_id: &'static str,
+            hard_low: f64,
+            safe_low: f64,
+            safe: f64,
+        },
+    }
+
+    /// Where a measurement sits relative to a corridor's thresholds.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum BandZone {
+        /// At or below `safe`.
+        Safe,
+        /// Above `safe`, at or below `gold`.
+        Gold,
+        /// Above `gold`, at or below `hard`.
+        Hard,
+        /// Above `hard`.
+        Over,
     }
 }
 
 pub mod risk {
-    use super::bands::CorridorBands;
+    use super::bands::{BandZone, CorridorBands};
+    use thiserror::Error;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Error)]
+    pub enum ResidualError {
+        #[error("No corridors evaluated: residual is undefined")]
+        NoCorridors,
+    }
 
     /// Single normalized risk coordinate r_x in [0, 1] with uncertainty.
     #[derive(Clone, Debug)]
@@ -145,7 +731,12 @@ pub mod risk {
         pub var_id: &'static str,
         pub value: f64,   // normalized risk coordinate r_x
         pub sigma: f64,   // uncertainty
+        /// `sigma` comes from the sensor rather than an `evaluate_hive` placeholder.
+        pub sigma_measured: bool,
         pub bands: CorridorBands,
+        /// Measurement past a hard limit (`CorridorBands::is_violation`). `value`
+        /// clamps to 1.0 both at and past hard, so breach checks test this flag.
+        pub over: bool,
     }
 
     /// Aggregate residual V_t and decision flags for a hive step.
@@ -154,18 +745,78 @@ pub mod risk {
         pub vt: f64,
         pub coords: Vec<RiskCoord>,
         pub derate: bool,
+        /// How hard to throttle, in [0, 1]: the largest overshoot of any coord past
+        /// its gold band (see `derate_fraction`); 1.0 whenever `stop` is set.
+        pub derate_fraction: f64,
         pub stop: bool,
+        /// No corridor was evaluated (all unset or filtered out): `vt` is undefined
+        /// rather than a perfectly safe 0.0, and `stop` is forced as a fail-safe.
+        pub no_coverage: bool,
     }
 
     /// Piecewise-linear normalization into r_x using safegoldhard bands.
+    /// A zero-width safe..hard range is a step: 0.0 up to the hard limit and
+    /// 1.0 past it, matching the infinite `to_risk_lipschitz` of such a band.
     pub fn to_risk(measured: f64, bands: &CorridorBands) -> f64 {
-        if measured <= bands.safe {
-            0.0
-        } else if measured >= bands.hard {
+        if bands.hard <= bands.safe {
+            return if measured <= bands.hard { 0.0 } else { 1.0 };
+        }
+        match bands.zone(measured) {
+            BandZone::Safe => 0.0,
+            // [safe, gold] -> [0, 0.5]
+            BandZone::Gold => 0.5 * (measured - bands.safe) / (bands.gold - bands.safe),
+            // [gold, hard] -> [0.5, 1]; the narrower of the two segments is steeper.
+            BandZone::Hard => 0.5 + 0.5 * (measured - bands.gold) / (bands.hard - bands.gold),
+            BandZone::Over => 1.0,
+        }
+    }
+
+    /// `to_risk` extended below the band for two-sided corridors: risk ramps
+    /// linearly from 0 at `safe_low` to 1 at `hard_low`, and the larger of the
+    /// two sides is returned. One-sided bands give exactly `to_risk`.
+    pub fn to_risk_two_sided(measured: f64, bands: &CorridorBands) -> f64 {
+        let upper = to_risk(measured, bands);
+        if !bands.two_sided || measured >= bands.safe_low {
+            return upper;
+        }
+        let width = bands.safe_low - bands.hard_low;
+        let lower = if width > 0.0 {
+            ((bands.safe_low - measured) / width).min(1.0)
+        } else {
             1.0
+        };
+        upper.max(lower)
+    }
+
+    /// Lipschitz constant of `to_risk` in the measured value: the steeper of the
+    /// two segment slopes. Infinite when either segment is degenerate (a step).
+    pub fn to_risk_lipschitz(bands: &CorridorBands) -> f64 {
+        let lower = bands.gold - bands.safe;
+        let upper = bands.hard - bands.gold;
+        if lower <= 0.0 || upper <= 0.0 {
+            f64::INFINITY
+        } else {
+            (0.5 / lower).max(0.5 / upper)
+        }
+    }
+
+    /// Largest overshoot past the gold band across `coords`, normalized so gold
+    /// (r = 0.5 under `to_risk`) maps to 0.0 and the hard limit (r = 1) to 1.0.
+    pub fn derate_fraction(coords: &[RiskCoord]) -> f64 {
+        coords
+            .iter()
+            .map(|c| ((c.value - 0.5) / 0.5).clamp(0.0, 1.0))
+            .fold(0.0, f64::max)
+    }
+
+    /// Actuator scale in [0, 1]: 1.0 with every coordinate at or below gold, falling
+    /// linearly to 0.0 as the worst coordinate reaches its hard limit. A stopped
+    /// residual is always 0.0.
+    pub fn derate_factor(res: &Residual) -> f64 {
+        if res.stop {
+            0.0
         } else {
-            // Map [safe, hard] -> [0, 1]
-            (measured - bands.safe) / (bands.hard - bands.safe)
+            1.0 - derate_fraction(&res.coords)
         }
     }
 
@@ -176,11 +827,99 @@ pub mod risk {
             .map(|c| c.bands.weight * c.value)
             .sum()
     }
+
+    /// Per-coordinate terms `(var_id, w_j * r_j)` of V_t, largest first, so an
+    /// operator can see which variable drives a Critical decision.
+    pub fn residual_contributions(coords: &[RiskCoord]) -> Vec<(&'static str, f64)> {
+        let mut terms: Vec<(&'static str, f64)> =
+            coords.iter().map(|c| (c.var_id, c.bands.weight * c.value)).collect();
+        terms.sort_by(|a, b| b.1.total_cmp(&a.1));
+        terms
+    }
+
+    /// Worst-case V_t = sum_j w_j * min(r_j + sigma_j, 1) for safety decisions,
+    /// so uncertain readings count against the hive. `compute_residual` stays
+    /// the nominal estimate.
+    pub fn compute_residual_conservative(coords: &[RiskCoord]) -> f64 {
+        coords
+            .iter()
+            .map(|c| c.bands.weight * (c.value + c.sigma.abs()).min(1.0))
+            .sum()
+    }
+
+    /// Like `compute_residual`, but an empty coordinate set is a misconfiguration
+    /// rather than a perfectly safe hive.
+    pub fn compute_residual_checked(coords: &[RiskCoord]) -> Result<f64, ResidualError> {
+        if coords.is_empty() {
+            return Err(ResidualError::NoCorridors);
+        }
+        Ok(compute_residual(coords))
+    }
+
+    /// V_t averaged over active corridors (coords with nonzero weight), so hives
+    /// monitored on different numbers of corridors can be compared. 0.0 if none.
+    pub fn residual_per_active_corridor(res: &Residual) -> f64 {
+        let active = res.coords.iter().filter(|c| c.bands.weight != 0.0).count();
+        if active == 0 {
+            0.0
+        } else {
+            res.vt / active as f64
+        }
+    }
+
+    /// Source category of a corridor, keyed on its var_id.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum StressSource {
+        /// Driven by the surrounding landscape: `toxin_*`, `forage_*`, `forager_*`.
+        Landscape,
+        /// Intrinsic to the hive body: `hive_temp*`, `brood_temp*`, `hive_humidity*`.
+        Microclimate,
+        /// Any var_id not matched above.
+        Other,
+    }
+
+    pub fn stress_source(var_id: &str) -> StressSource {
+        if ["toxin_", "forage_", "forager_"].iter().any(|p| var_id.starts_with(p)) {
+            StressSource::Landscape
+        } else if ["hive_temp", "brood_temp", "hive_humidity"].iter().any(|p| var_id.starts_with(p)) {
+            StressSource::Microclimate
+        } else {
+            StressSource::Other
+        }
+    }
+
+    /// Each source's share of V_t; shares sum to 1.0 unless V_t is zero (all 0.0).
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct StressBreakdown {
+        pub landscape: f64,
+        pub microclimate: f64,
+        pub other: f64,
+    }
+
+    /// Split the weighted contributions w_j * r_j of a residual by `stress_source`.
+    pub fn stress_attribution(res: &Residual) -> StressBreakdown {
+        let mut out = StressBreakdown::default();
+        if res.vt <= 0.0 {
+            return out;
+        }
+        for c in &res.coords {
+            let share = c.bands.weight * c.value / res.vt;
+            match stress_source(c.var_id) {
+                StressSource::Landscape => out.landscape += share,
+                StressSource::Microclimate => out.microclimate += share,
+                StressSource::Other => out.other += share,
+            }
+        }
+        out
+    }
 }
 
 pub mod hive {
-    use super::bands::CorridorBands;
-    use super::risk::{compute_residual, to_risk, Residual, RiskCoord};
+    use super::bands::{BandError, BandZone, CorridorBands};
+    use super::risk::{
+        compute_residual_checked, derate_fraction, to_risk, to_risk_two_sided, Residual, RiskCoord,
+    };
+    use thiserror::Error;
 
     /// Bee-centered envelope: no human fields; only hive and landscape metrics.
     #[derive(Clone, Debug)]
@@ -231,242 +970,3217 @@ pub mod hive {
         pub forager_load_bands: CorridorBands,
     }
 
-    /// Policy thresholds summarized as KER for the hive corridor state.
-    #[derive(Clone, Debug)]
-    pub struct HiveKER {
-        pub knowledge_factor: f64,   // 0–1 coverage of critical bee variables
-        pub eco_impact: f64,         // 0–1 eco benefit kernel
-        pub risk_of_harm: f64,       // 0–1 residual corridor penetration
+    /// Editable numeric field of a `CorridorBands`.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum BandField {
+        Safe,
+        Gold,
+        Hard,
+        Weight,
     }
 
-    /// No-corridor, no-build invariant: all mandatory corridors must be present
-    /// and well-formed before any hive can be admitted to the governed stack.
-    pub fn corridor_present(c: &HiveCorridors) -> bool {
-        let bands = [
-            &c.temp_bands,
-            &c.brood_temp_bands,
-            &c.humidity_bands,
-            &c.toxin_air_bands,
-            &c.toxin_wax_bands,
-            &c.forage_radius_bands,
-            &c.forager_load_bands,
-        ];
+    /// One change to the band identified by `var_id`.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct BandEdit {
+        pub var_id: String,
+        pub field: BandField,
+        pub value: f64,
+    }
 
-        bands.iter().all(|b| {
-            (!b.mandatory) || (b.hard > 0.0 && b.gold <= b.hard && b.safe <= b.gold)
-        })
+    /// Before/after record of an applied `BandEdit`.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct CorridorDiff {
+        pub var_id: &'static str,
+        pub field: BandField,
+        pub old: f64,
+        pub new: f64,
     }
 
-    /// Compute hive residual and band (Safe / Warning / Critical).
-    pub fn evaluate_hive(env: &HiveEnvelope, corridors: &HiveCorridors) -> Residual {
-        let coords = vec![
-            RiskCoord {
-                var_id: corridors.temp_bands.var_id,
-                sigma: 0.05,
-                value: to_risk(env.hive_temp_c, &corridors.temp_bands),
-                bands: corridors.temp_bands.clone(),
-            },
-            RiskCoord {
-                var_id: corridors.brood_temp_bands.var_id,
-                sigma: 0.05,
-                value: to_risk(env.brood_temp_c, &corridors.brood_temp_bands),
-                bands: corridors.brood_temp_bands.clone(),
-            },
-            RiskCoord {
-                var_id: corridors.humidity_bands.var_id,
-                sigma: 0.05,
-                value: to_risk(env.hive_humidity_pct, &corridors.humidity_bands),
-                bands: corridors.humidity_bands.clone(),
-            },
-            RiskCoord {
-                var_id: corridors.toxin_air_bands.var_id,
-                sigma: 0.10,
-                value: to_risk(env.toxin_index_air, &corridors.toxin_air_bands),
-                bands: corridors.toxin_air_bands.clone(),
-            },
-            RiskCoord {
-                var_id: corridors.toxin_wax_bands.var_id,
-                sigma: 0.10,
-                value: to_risk(env.toxin_index_wax, &corridors.toxin_wax_bands),
-                bands: corridors.toxin_wax_bands.clone(),
-            },
-            RiskCoord {
-                var_id: corridors.forage_radius_bands.var_id,
-                sigma: 0.05,
-                value: to_risk(env.forage_radius_km, &corridors.forage_radius_bands),
-                bands: corridors.forage_radius_bands.clone(),
-            },
-            RiskCoord {
-                var_id: corridors.forager_load_bands.var_id,
-                sigma: 0.05,
-                value: to_risk(env.forager_load_pct, &corridors.forager_load_bands),
-                bands: corridors.forager_load_bands.clone(),
-            },
-        ];
+    #[derive(Debug, Clone, PartialEq, Error)]
+    pub enum CorridorError {
+        #[error("No corridor with var_id {0}")]
+        UnknownCorridor(String),
+        #[error("Edits leave corridor {0} malformed (see CorridorBands::validate)")]
+        Malformed(&'static str),
+    }
 
-        let vt = compute_residual(&coords);
+    /// Builder for `HiveCorridors`; see `HiveCorridors::builder`.
+    #[derive(Clone, Debug)]
+    pub struct HiveCorridorsBuilder {
+        corridors: HiveCorridors,
+    }
 
-        let mut derate = false;
-        let mut stop = false;
+    /// Placeholder for a corridor the caller did not configure: non-mandatory with
+    /// all-zero bands, so `evaluate_hive` skips it.
+    fn unset_band(var_id: &'static str, units: &'static str, lyap_channel: u32) -> CorridorBands {
+        CorridorBands::new(var_id, units, 0.0, 0.0, 0.0, 0.0, lyap_channel, false)
+    }
 
-        for c in &coords {
-            if c.value >= 1.0 {
-                // Hard violation: hive in critical corridor → stop.
-                stop = true;
-            } else if c.value > c.bands.gold {
-                // Between gold and hard: derate.
-                derate = true;
-            }
+    impl HiveCorridorsBuilder {
+        pub fn with_temp_bands(mut self, bands: CorridorBands) -> Self {
+            self.corridors.temp_bands = bands;
+            self
         }
 
-        Residual { vt, coords, derate, stop }
-    }
+        pub fn with_brood_temp_bands(mut self, bands: CorridorBands) -> Self {
+            self.corridors.brood_temp_bands = bands;
+            self
+        }
 
-    /// Runtime invariant: no adjustment may increase bee risk or violate hard limits.
-    /// This is the "safestep" analogue for hives.
-    pub fn safe_step(prev: &Residual, next: &Residual) -> Residual {
-        let mut decision = next.clone();
+        pub fn with_humidity_bands(mut self, bands: CorridorBands) -> Self {
+            self.corridors.humidity_bands = bands;
+            self
+        }
 
-        // Lyapunov monotonicity outside the safe interior.
-        if next.vt > prev.vt && prev.coords.iter().any(|c| c.value > 0.0) {
-            decision.derate = true;
-            decision.stop = true;
+        pub fn with_toxin_air_bands(mut self, bands: CorridorBands) -> Self {
+            self.corridors.toxin_air_bands = bands;
+            self
         }
 
-        // Any hard-limit violation in next state forces stop.
-        for c in &next.coords {
-            if c.value >= 1.0 {
-                decision.stop = true;
-            }
+        pub fn with_toxin_wax_bands(mut self, bands: CorridorBands) -> Self {
+            self.corridors.toxin_wax_bands = bands;
+            self
         }
 
-        decision
-    }
+        pub fn with_forage_radius_bands(mut self, bands: CorridorBands) -> Self {
+            self.corridors.forage_radius_bands = bands;
+            self
+        }
 
-    /// Example policy: no action may increase pesticide exposure, raise hive
-    /// temperature above safe band, or reduce forage radius below corridor.
-    pub fn policy_allows_adjustment(
-        envelope_before: &HiveEnvelope,
-        envelope_after: &HiveEnvelope,
-        corridors: &HiveCorridors,
-    ) -> bool {
-        // Pesticide / toxin invariants (monotone non-increasing).
-        if envelope_after.toxin_index_air > envelope_before.toxin_index_air {
-            return false;
+        pub fn with_forager_load_bands(mut self, bands: CorridorBands) -> Self {
+            self.corridors.forager_load_bands = bands;
+            self
         }
-        if envelope_after.toxin_index_wax > envelope_before.toxin_index_wax {
-            return false;
+
+        pub fn build(self) -> HiveCorridors {
+            self.corridors
         }
+    }
 
-        // Hive temperature must not move from <= safe band to > safe band.
-        let r_before_temp =
-            super::risk::to_risk(envelope_before.hive_temp_c, &corridors.temp_bands);
-        let r_after_temp =
-            super::risk::to_risk(envelope_after.hive_temp_c, &corridors.temp_bands);
-        if r_before_temp <= corridors.temp_bands.safe && r_after_temp > corridors.temp_bands.safe {
-            return false;
+    impl HiveCorridors {
+        /// Start from seven unset corridors (non-mandatory, all-zero bands) and
+        /// override only the ones a caller cares about.
+        pub fn builder() -> HiveCorridorsBuilder {
+            HiveCorridorsBuilder {
+                corridors: HiveCorridors {
+                    temp_bands: unset_band("hive_temp_c", "C", 0),
+                    brood_temp_bands: unset_band("brood_temp_c", "C", 1),
+                    humidity_bands: unset_band("hive_humidity_pct", "%", 2),
+                    toxin_air_bands: unset_band("toxin_index_air", "ppb", 3),
+                    toxin_wax_bands: unset_band("toxin_index_wax", "ppb", 4),
+                    forage_radius_bands: unset_band("forage_radius_km", "km", 5),
+                    forager_load_bands: unset_band("forager_load_pct", "fraction", 6),
+                },
+            }
         }
 
-        // Forage radius must not shrink below safe band.
-        let r_before_forage = super::risk::to_risk(
-            envelope_before.forage_radius_km,
-            &corridors.forage_radius_bands,
-        );
-        let r_after_forage = super::risk::to_risk(
-            envelope_after.forage_radius_km,
-            &corridors.forage_radius_bands,
-        );
-        if r_after_forage > r_before_forage {
-            return false;
+        fn bands(&self) -> [&CorridorBands; 7] {
+            [
+                &self.temp_bands,
+                &self.brood_temp_bands,
+                &self.humidity_bands,
+                &self.toxin_air_bands,
+                &self.toxin_wax_bands,
+                &self.forage_radius_bands,
+                &self.forager_load_bands,
+            ]
         }
 
-        true
-    }
-}
+        fn bands_mut(&mut self) -> [&mut CorridorBands; 7] {
+            [
+                &mut self.temp_bands,
+                &mut self.brood_temp_bands,
+                &mut self.humidity_bands,
+                &mut self.toxin_air_bands,
+                &mut self.toxin_wax_bands,
+                &mut self.forage_radius_bands,
+                &mut self.forager_load_bands,
+            ]
+        }
 
-impl HiveInnerLedger {
-    pub fn new() -> Self {
-        Self { events: Vec::new() }
-    }
+        /// Apply `edits` as one transaction: they are made on a working copy, which
+        /// replaces `self` only if `corridor_present` still holds. On error `self`
+        /// is unchanged. Returns one diff per edit, in order.
+        pub fn edit(&mut self, edits: Vec<BandEdit>) -> Result<Vec<CorridorDiff>, CorridorError> {
+            let mut work = self.clone();
+            let mut diffs = Vec::with_capacity(edits.len());
+            for e in edits {
+                let band = work
+                    .bands_mut()
+                    .into_iter()
+                    .find(|b| b.var_id == e.var_id)
+                    .ok_or_else(|| CorridorError::UnknownCorridor(e.var_id.clone()))?;
+                let slot = match e.field {
+                    BandField::Safe => &mut band.safe,
+                    BandField::Gold => &mut band.gold,
+                    BandField::Hard => &mut band.hard,
+                    BandField::Weight => &mut band.weight,
+                };
+                diffs.push(CorridorDiff {
+                    var_id: band.var_id,
+                    field: e.field,
+                    old: *slot,
+                    new: e.value,
+                });
+                *slot = e.value;
+            }
 
-    pub fn events(&self) -> &[HiveLedgerEvent] {
-        &self.events
+            if let Some(&(bad, _)) = corridor_report(&work).first() {
+                return Err(CorridorError::Malformed(bad));
+            }
+            *self = work;
+            Ok(diffs)
+        }
     }
 
-    /// Apply a HiveSystemAdjustment under hard bee-rights invariants.
-    pub fn apply_adjustment(
-        &mut self,
-        mut env: HiveEnvelope,
-        adj: HiveSystemAdjustment,
-    ) -> Result<HiveEnvelope, HiveGuardError> {
+    /// Policy thresholds summarized as KER for the hive corridor state.
+    #[derive(Clone, Debug)]
+    pub struct HiveKER {
+        pub knowledge_factor: f64,   // 0–1 coverage of critical bee variables
+        pub eco_impact: f64,         // 0–1 eco benefit kernel
+        pub risk_of_harm: f64,       // 0–1 residual corridor penetration
+    }
+
+    /// Placeholder sigma `evaluate_hive` assigns to physical hive metrics.
+    pub const DEFAULT_SIGMA: f64 = 0.05;
+    /// Placeholder sigma `evaluate_hive` assigns to toxin indices.
+    pub const DEFAULT_TOXIN_SIGMA: f64 = 0.10;
+
+    /// Derive KER from an evaluated residual. `knowledge_factor` is the fraction of
+    /// coordinates whose sigma is measured (`RiskCoord::sigma_measured`), `eco_impact` maps
+    /// `eco_band` Safe/Warning/Critical to 1.0/0.5/0.0, and `risk_of_harm` is V_t
+    /// over the total weight. A residual with no weighted coordinates is full risk.
+    pub fn ker_from_residual(env: &HiveEnvelope, residual: &Residual) -> HiveKER {
+        let n = residual.coords.len();
+        let measured = residual
+            .coords
+            .iter()
+            .filter(|c| c.sigma_measured)
+            .count();
+        let knowledge_factor = if n == 0 { 0.0 } else { measured as f64 / n as f64 };
+        let eco_impact = match env.eco_band {
+            EcoBand::Safe => 1.0,
+            EcoBand::Warning => 0.5,
+            EcoBand::Critical => 0.0,
+        };
+        let total_weight: f64 = residual.coords.iter().map(|c| c.bands.weight).sum();
+        let risk_of_harm = if total_weight > 0.0 {
+            (residual.vt / total_weight).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        HiveKER { knowledge_factor, eco_impact, risk_of_harm }
+    }
+
+    /// Admission gate: enough of the corridors are measured and risk stays bounded.
+    /// Both thresholds are inclusive.
+    pub fn ker_admits(ker: &HiveKER, min_knowledge: f64, max_risk: f64) -> bool {
+        ker.knowledge_factor >= min_knowledge && ker.risk_of_harm <= max_risk
+    }
+
+    /// Why a mandatory corridor fails `CorridorBands::validate`; NaN counts as a defect.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum CorridorDefect {
+        /// `hard <= 0`.
+        NonPositiveHard,
+        /// `gold > hard`.
+        GoldAboveHard,
+        /// `safe > gold`.
+        SafeAboveGold,
+        /// `safe < 0`.
+        NegativeSafe,
+        /// `weight < 0`.
+        NegativeWeight,
+        /// Two-sided band without `hard_low <= safe_low <= safe`.
+        LowBandsUnordered,
+    }
+
+    impl From<&BandError> for CorridorDefect {
+        /// Narrow an `Unordered` error to the first broken inequality, checking
+        /// `hard` first, then `gold <= hard`, `safe <= gold` and `0 <= safe`.
+        fn from(err: &BandError) -> Self {
+            let bad = |lo: f64, hi: f64| lo.is_nan() || hi.is_nan() || lo > hi;
+            match *err {
+                BandError::Unordered { safe, gold, hard, .. } => {
+                    if hard.is_nan() || hard <= 0.0 {
+                        CorridorDefect::NonPositiveHard
+                    } else if bad(gold, hard) {
+                        CorridorDefect::GoldAboveHard
+                    } else if bad(safe, gold) {
+                        CorridorDefect::SafeAboveGold
+                    } else {
+                        CorridorDefect::NegativeSafe
+                    }
+                }
+                BandError::NegativeWeight { .. } => CorridorDefect::NegativeWeight,
+                BandError::UnorderedLow { .. } => CorridorDefect::LowBandsUnordered,
+            }
+        }
+    }
+
+    fn band_defect(b: &CorridorBands) -> Option<CorridorDefect> {
+        b.validate().err().map(|e| CorridorDefect::from(&e))
+    }
+
+    /// Every mandatory corridor that is malformed, with its first defect, in
+    /// `HiveCorridors` field order. Optional corridors are never reported.
+    pub fn corridor_report(c: &HiveCorridors) -> Vec<(&'static str, CorridorDefect)> {
+        c.bands()
+            .into_iter()
+            .filter(|b| b.mandatory)
+            .filter_map(|b| band_defect(b).map(|d| (b.var_id, d)))
+            .collect()
+    }
+
+    /// No-corridor, no-build invariant: all mandatory corridors must be present
+    /// and well-formed before any hive can be admitted to the governed stack.
+    pub fn corridor_present(c: &HiveCorridors) -> bool {
+        corridor_report(c).is_empty()
+    }
+
+    /// Compute hive residual and band (Safe / Warning / Critical).
+    pub fn evaluate_hive(env: &HiveEnvelope, corridors: &HiveCorridors) -> Residual {
+        // (bands, raw measurement, sigma) per corridor.
+        let inputs: [(&CorridorBands, f64, f64); 7] = [
+            (&corridors.temp_bands, env.hive_temp_c, DEFAULT_SIGMA),
+            (&corridors.brood_temp_bands, env.brood_temp_c, DEFAULT_SIGMA),
+            (&corridors.humidity_bands, env.hive_humidity_pct, DEFAULT_SIGMA),
+            (&corridors.toxin_air_bands, env.toxin_index_air, DEFAULT_TOXIN_SIGMA),
+            (&corridors.toxin_wax_bands, env.toxin_index_wax, DEFAULT_TOXIN_SIGMA),
+            (&corridors.forage_radius_bands, env.forage_radius_km, DEFAULT_SIGMA),
+            (&corridors.forager_load_bands, env.forager_load_pct, DEFAULT_SIGMA),
+        ];
+        // Non-mandatory corridors with no hard limit are unset, not violated.
+        let inputs: Vec<(&CorridorBands, f64, f64)> = inputs
+            .into_iter()
+            .filter(|(bands, _, _)| bands.mandatory || bands.hard > 0.0)
+            .collect();
+        let coords: Vec<RiskCoord> = inputs
+            .iter()
+            .map(|&(bands, measured, sigma)| RiskCoord {
+                var_id: bands.var_id,
+                sigma,
+                sigma_measured: false,
+                value: to_risk_two_sided(measured, bands),
+                bands: bands.clone(),
+                over: bands.is_violation(measured),
+            })
+            .collect();
+
+        let mut derate = false;
+        let mut stop = false;
+
+        // No corridors evaluated is a misconfiguration: flag it and fail safe by
+        // stopping instead of reporting a zero (perfectly safe) residual.
+        let (vt, no_coverage) = match compute_residual_checked(&coords) {
+            Ok(vt) => (vt, false),
+            Err(_) => {
+                stop = true;
+                (0.0, true)
+            }
+        };
+
+        for &(bands, measured, _) in &inputs {
+            match bands.zone_two_sided(measured) {
+                // Past a hard limit (`is_violation`'s convention): critical corridor → stop.
+                BandZone::Over => stop = true,
+                // Between gold and hard, on either side of a two-sided band: derate.
+                BandZone::Hard => derate = true,
+                BandZone::Safe | BandZone::Gold => {}
+            }
+        }
+
+        let derate_fraction = if stop { 1.0 } else { derate_fraction(&coords) };
+        Residual { vt, coords, derate, derate_fraction, stop, no_coverage }
+    }
+
+    /// Uncertainty rule for `evaluate_hive_gated`: a coordinate counts as past gold
+    /// when its upper confidence bound `value + z * sigma` exceeds r = 0.5, the
+    /// gold band under `to_risk`.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub struct ConfidenceGate {
+        /// Standard deviations above the point estimate, e.g. 1.645 for a
+        /// one-sided 95% bound.
+        pub z: f64,
+    }
+
+    /// `evaluate_hive`, additionally derating when any coordinate's upper
+    /// confidence bound crosses gold even though its point estimate does not, so
+    /// uncertain readings trigger caution. `derate_fraction` then reflects the
+    /// upper bounds.
+    pub fn evaluate_hive_gated(
+        env: &HiveEnvelope,
+        corridors: &HiveCorridors,
+        gate: &ConfidenceGate,
+    ) -> Residual {
+        let mut res = evaluate_hive(env, corridors);
+        if res.stop {
+            return res;
+        }
+        let upper = |c: &RiskCoord| c.value + gate.z * c.sigma.max(0.0);
+        if res.coords.iter().any(|c| upper(c) > 0.5) {
+            res.derate = true;
+            let gated = res
+                .coords
+                .iter()
+                .map(|c| ((upper(c) - 0.5) / 0.5).clamp(0.0, 1.0))
+                .fold(0.0, f64::max);
+            res.derate_fraction = res.derate_fraction.max(gated);
+        }
+        res
+    }
+
+    /// Barrier added to `safety_cost` for each coordinate past its hard limit.
+    /// Chosen to dwarf any reachable residual, since V_t is bounded by the sum of weights.
+    pub const SAFETY_BARRIER: f64 = 1.0e6;
+
+    /// Scalar minimization objective for external optimizers: the residual V_t
+    /// plus `SAFETY_BARRIER` per hard-limit breach. Continuous below every hard
+    /// limit, with a step of at least `SAFETY_BARRIER` just past it.
+    pub fn safety_cost(env: &HiveEnvelope, corridors: &HiveCorridors) -> f64 {
+        let res = evaluate_hive(env, corridors);
+        let breaches = res.coords.iter().filter(|c| c.over).count();
+        res.vt + SAFETY_BARRIER * breaches as f64
+    }
+
+    /// Runtime invariant: no adjustment may increase bee risk or violate hard limits.
+    /// This is the "safestep" analogue for hives. Increases of V_t up to `margin`
+    /// are treated as sensor noise; hard-limit violations always stop.
+    pub fn safe_step(prev: &Residual, next: &Residual, margin: f64) -> Residual {
+        let mut decision = next.clone();
+
+        // Lyapunov monotonicity outside the safe interior.
+        if next.vt > prev.vt + margin && prev.coords.iter().any(|c| c.value > 0.0) {
+            decision.derate = true;
+            decision.stop = true;
+        }
+
+        // Any hard-limit violation in next state forces stop; `decision` already
+        // carries `next.stop`.
+        if next.coords.iter().any(|c| c.over) {
+            decision.stop = true;
+        }
+
+        decision.derate_fraction = if decision.stop {
+            1.0
+        } else {
+            super::risk::derate_fraction(&next.coords)
+        };
+        decision
+    }
+
+    /// V_t thresholds for a latched stop: enter at or above `enter`, release only
+    /// once V_t falls below `exit` (`exit < enter` gives a dead band).
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub struct StopHysteresis {
+        pub enter: f64,
+        pub exit: f64,
+    }
+
+    /// Latch carried between `safe_step_hysteretic` calls for one hive.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct StopState {
+        pub latched: bool,
+    }
+
+    /// `safe_step` with a latched stop, so V_t hovering near a limit cannot toggle
+    /// `stop` every step. Any `safe_step` stop (with its noise `margin`) or
+    /// V_t >= `enter` latches; the latch clears once V_t < `exit` with no other
+    /// stop cause.
+    pub fn safe_step_hysteretic(
+        state: &mut StopState,
+        prev: &Residual,
+        next: &Residual,
+        h: &StopHysteresis,
+        margin: f64,
+    ) -> Residual {
+        let mut decision = safe_step(prev, next, margin);
+        if decision.stop || next.vt >= h.enter {
+            state.latched = true;
+        } else if next.vt < h.exit {
+            state.latched = false;
+        }
+        if state.latched {
+            decision.stop = true;
+            decision.derate = true;
+            decision.derate_fraction = 1.0;
+        }
+        decision
+    }
+
+    /// Reducer from per-hive residuals to one regional risk.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub enum RegionalAggregator {
+        /// Largest V_t.
+        Worst,
+        /// Mean V_t.
+        Mean,
+        /// Linearly interpolated percentile of V_t, `p` in [0, 100]; NaN means worst.
+        Percentile(f64),
+    }
+
+    /// Regional risk over `residuals` by `agg`; 0.0 for an empty region.
+    pub fn regional_risk(residuals: &[Residual], agg: RegionalAggregator) -> f64 {
+        if residuals.is_empty() {
+            return 0.0;
+        }
+        let mut vts: Vec<f64> = residuals.iter().map(|r| r.vt).collect();
+        vts.sort_by(f64::total_cmp);
+        match agg {
+            RegionalAggregator::Worst => vts[vts.len() - 1],
+            RegionalAggregator::Mean => vts.iter().sum::<f64>() / vts.len() as f64,
+            RegionalAggregator::Percentile(p) => {
+                let p = if p.is_nan() { 100.0 } else { p.clamp(0.0, 100.0) };
+                let rank = p / 100.0 * (vts.len() - 1) as f64;
+                let (lo, hi) = (rank.floor() as usize, rank.ceil() as usize);
+                vts[lo] + (vts[hi] - vts[lo]) * (rank - lo as f64)
+            }
+        }
+    }
+
+    /// Outcome of `safe_step_lookahead` over a predicted residual sequence.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct StepDecision {
+        pub stop: bool,
+        /// Index of the first predicted step with a hard-limit breach.
+        pub breach_index: Option<usize>,
+    }
+
+    /// Multi-step analogue of `safe_step`: stop now if any residual in the
+    /// predicted sequence has a coordinate past its hard limit.
+    pub fn safe_step_lookahead(residuals: &[Residual]) -> StepDecision {
+        let breach_index = residuals
+            .iter()
+            .position(|r| r.coords.iter().any(|c| c.over));
+        StepDecision {
+            stop: breach_index.is_some(),
+            breach_index,
+        }
+    }
+
+    /// Rule of `policy_check` an adjustment violated.
+    #[derive(Debug, Clone, PartialEq, Error)]
+    pub enum PolicyRejection {
+        #[error("Air toxin index would rise from {before} to {after}")]
+        ToxinAirIncrease { before: f64, after: f64 },
+        #[error("Wax toxin index would rise from {before} to {after}")]
+        ToxinWaxIncrease { before: f64, after: f64 },
+        #[error("Hive temperature would leave its safe band: {before} C -> {after} C")]
+        TemperatureCrossing { before: f64, after: f64 },
+        #[error("Forage corridor risk would rise from {before} to {after}")]
+        ForageShrink { before: f64, after: f64 },
+    }
+
+    /// Example policy: no action may increase pesticide exposure, raise hive
+    /// temperature above safe band, or reduce forage radius below corridor.
+    /// Rules are checked in that order and the first violation is returned.
+    pub fn policy_check(
+        envelope_before: &HiveEnvelope,
+        envelope_after: &HiveEnvelope,
+        corridors: &HiveCorridors,
+    ) -> Result<(), PolicyRejection> {
+        // Pesticide / toxin invariants (monotone non-increasing).
+        let (before, after) = (envelope_before.toxin_index_air, envelope_after.toxin_index_air);
+        if after > before {
+            return Err(PolicyRejection::ToxinAirIncrease { before, after });
+        }
+        let (before, after) = (envelope_before.toxin_index_wax, envelope_after.toxin_index_wax);
+        if after > before {
+            return Err(PolicyRejection::ToxinWaxIncrease { before, after });
+        }
+
+        // Hive temperature must not move from <= safe band to > safe band.
+        let (before, after) = (envelope_before.hive_temp_c, envelope_after.hive_temp_c);
+        let bands = &corridors.temp_bands;
+        if bands.zone(before) == BandZone::Safe && bands.zone(after) != BandZone::Safe {
+            return Err(PolicyRejection::TemperatureCrossing { before, after });
+        }
+
+        // Forage radius must not shrink below safe band.
+        let before = to_risk(envelope_before.forage_radius_km, &corridors.forage_radius_bands);
+        let after = to_risk(envelope_after.forage_radius_km, &corridors.forage_radius_bands);
+        if after > before {
+            return Err(PolicyRejection::ForageShrink { before, after });
+        }
+
+        Ok(())
+    }
+
+    /// `policy_check` as a plain verdict.
+    pub fn policy_allows_adjustment(
+        envelope_before: &HiveEnvelope,
+        envelope_after: &HiveEnvelope,
+        corridors: &HiveCorridors,
+    ) -> bool {
+        policy_check(envelope_before, envelope_after, corridors).is_ok()
+    }
+}
+
+impl HiveInnerLedger {
+    pub fn new() -> Self {
+        Self {
+            events: Vec::new(),
+            rejected: Vec::new(),
+            by_id: HashMap::new(),
+        }
+    }
+
+    pub fn events(&self) -> &[HiveLedgerEvent] {
+        &self.events
+    }
+
+    /// Accepted events for one hive, ordered by adjustment timestamp.
+    pub fn events_for(&self, hive_id: &str) -> Vec<&HiveLedgerEvent> {
+        let mut events: Vec<&HiveLedgerEvent> = self
+            .events
+            .iter()
+            .filter(|e| e.adjustment.hive_id == hive_id)
+            .collect();
+        events.sort_by_key(|e| e.adjustment.timestamp);
+        events
+    }
+
+    /// Totals over events whose `adjustment.timestamp` is at or after `since`.
+    pub fn summarize(&self, since: DateTime<Utc>) -> LedgerSummary {
+        let mut summary = LedgerSummary::default();
+        for e in self.events.iter().filter(|e| e.adjustment.timestamp >= since) {
+            let temp = e.post_envelope.hive_temperature_c;
+            summary.event_count += 1;
+            summary.net_delta_eco_impact_score_corridor += e.adjustment.delta_eco_impact_score_corridor;
+            summary.net_delta_forage_radius_m += e.adjustment.delta_forage_radius_m;
+            let (lo, hi) = (summary.min_projected_temp_c, summary.max_projected_temp_c);
+            summary.min_projected_temp_c = Some(lo.map_or(temp, |t| t.min(temp)));
+            summary.max_projected_temp_c = Some(hi.map_or(temp, |t| t.max(temp)));
+        }
+        summary
+    }
+
+    pub fn rejections(&self) -> &[(HiveSystemAdjustment, HiveGuardError)] {
+        &self.rejected
+    }
+
+    /// Serialize the full history (accepted events and rejections) so it survives restarts.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Reload a ledger written by `to_json`.
+    pub fn from_json(s: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(s)
+    }
+
+    /// Like `apply_adjustment`, but also records denied attempts in the rejection log
+    /// so intervention friction can be audited.
+    pub fn try_apply(
+        &mut self,
+        env: HiveEnvelope,
+        adj: HiveSystemAdjustment,
+    ) -> Result<HiveEnvelope, HiveGuardError> {
+        let attempted = adj.clone();
+        self.apply_adjustment(env, adj).inspect_err(|err| {
+            self.rejected.push((attempted, err.clone()));
+        })
+    }
+
+    /// Evaluate every hard bee-rights invariant for `adj` applied to `env` and
+    /// return all violations, in invariant order, without mutating anything.
+    pub fn check_adjustment(
+        &self,
+        env: &HiveEnvelope,
+        adj: &HiveSystemAdjustment,
+    ) -> Vec<HiveGuardError> {
+        let mut violations = Vec::new();
+
         // Invariants:
         // - no action may increase pesticide exposure
         if adj.delta_pesticide_exposure_ppb > 0.0 {
-            return Err(HiveGuardError::IncreasesPesticideExposure);
+            violations.push(HiveGuardError::IncreasesPesticideExposure);
         }
         // - no action may raise hive temperature above safe band
-        let projected_temp = env.hive_temperature_c
-            + temp_delta_from_shade(adj.delta_shade_fraction);
+        let projected_temp = env.hive_temperature_c + projected_temp_delta(env, adj);
         if projected_temp > env.safe_temperature_c_max {
-            return Err(HiveGuardError::RaisesHiveTemperature);
+            violations.push(HiveGuardError::RaisesHiveTemperature);
         }
         // - no action may reduce forage radius below X (safe_forage_radius_m_min)
         let projected_radius = env.forage_radius_m + adj.delta_forage_radius_m;
         if projected_radius < env.safe_forage_radius_m_min {
-            return Err(HiveGuardError::ReducesForageRadius);
+            violations.push(HiveGuardError::ReducesForageRadius);
         }
         // - artificial light and noise cannot increase
         if adj.delta_artificial_light_nits > 0.0 || adj.delta_noise_db > 0.0 {
-            return Err(HiveGuardError::IncreasesLightOrNoise);
+            violations.push(HiveGuardError::IncreasesLightOrNoise);
         }
         // - eco impact score corridor must be non-decreasing (monotone inequality)
         if adj.delta_eco_impact_score_corridor < 0.0 {
-            return Err(HiveGuardError::DecreasesEcoImpactScore);
+            violations.push(HiveGuardError::DecreasesEcoImpactScore);
+        }
+        // - an all-zero adjustment would only waste a ledger slot
+        if adj.is_no_op() {
+            violations.push(HiveGuardError::NoEffect);
+        }
+        // - the resulting envelope must itself pass `HiveEnvelope::validate`
+        if let Err(err) = project_adjustment(env, adj).validate() {
+            violations.push(err.into());
         }
 
-        // All invariants pass, update envelope.
-        env.ambient_toxin_ppb += adj.delta_pesticide_exposure_ppb;
-        env.forage_radius_m = projected_radius;
-        env.forage_diversity_index =
-            (env.forage_diversity_index + adj.delta_forage_diversity_index).clamp(0.0, 1.0);
-        env.hive_temperature_c = projected_temp;
-        env.eco_impact_score_corridor += adj.delta_eco_impact_score_corridor;
-        env.eco_band = env.evaluate_band();
+        violations
+    }
+
+    /// Most recent accepted event whose adjustment has this id.
+    fn applied(&self, id: &str) -> Option<&HiveLedgerEvent> {
+        self.by_id.get(id).map(|&i| &self.events[i])
+    }
+
+    /// Append an accepted event and index it by adjustment id.
+    fn record(&mut self, event: HiveLedgerEvent) {
+        self.by_id.insert(event.adjustment.id.clone(), self.events.len());
+        self.events.push(event);
+    }
+
+    /// Apply a HiveSystemAdjustment under hard bee-rights invariants.
+    /// Returns the first violated invariant, if any; see `check_adjustment` for all of them.
+    ///
+    /// Adjustment ids are idempotency keys: resubmitting an already applied
+    /// adjustment returns its recorded post-envelope without applying it again,
+    /// and reusing its id for different contents is a `DuplicateAdjustment`.
+    pub fn apply_adjustment(
+        &mut self,
+        env: HiveEnvelope,
+        adj: HiveSystemAdjustment,
+    ) -> Result<HiveEnvelope, HiveGuardError> {
+        if let Some(prior) = self.applied(&adj.id) {
+            return if prior.adjustment == adj {
+                Ok(prior.post_envelope.clone())
+            } else {
+                Err(HiveGuardError::DuplicateAdjustment(adj.id))
+            };
+        }
+        env.validate()?;
+        if let Some(first) = self.check_adjustment(&env, &adj).into_iter().next() {
+            return Err(first);
+        }
 
-        let event = HiveLedgerEvent {
+        // All invariants pass, update envelope.
+        let post = project_adjustment(&env, &adj);
+        self.record(HiveLedgerEvent {
             adjustment: adj,
-            pre_envelope: env.clone(),
-            post_envelope: env.clone(),
+            pre_envelope: env,
+            post_envelope: post.clone(),
+        });
+        Ok(post)
+    }
+
+    /// Apply every item or none: all adjustments are checked first, and ledger events
+    /// are appended only if each one passes. On failure returns the index of the
+    /// first offending item and its first violated invariant; the ledger is untouched.
+    ///
+    /// Ids are idempotency keys as in `apply_adjustment`: an exact replay of an applied
+    /// adjustment, or of an earlier item in the batch, yields the recorded post-envelope
+    /// without a new event, while reusing an id for different contents is a
+    /// `DuplicateAdjustment`.
+    pub fn apply_batch(
+        &mut self,
+        items: Vec<(HiveEnvelope, HiveSystemAdjustment)>,
+    ) -> Result<Vec<HiveEnvelope>, (usize, HiveGuardError)> {
+        for (i, (env, adj)) in items.iter().enumerate() {
+            let prior = self
+                .applied(&adj.id)
+                .map(|e| &e.adjustment)
+                .or_else(|| items[..i].iter().map(|(_, a)| a).find(|a| a.id == adj.id));
+            match prior {
+                Some(prior) if prior == adj => continue,
+                Some(_) => return Err((i, HiveGuardError::DuplicateAdjustment(adj.id.clone()))),
+                None => {}
+            }
+            env.validate().map_err(|e| (i, e.into()))?;
+            if let Some(first) = self.check_adjustment(env, adj).into_iter().next() {
+                return Err((i, first));
+            }
+        }
+
+        let mut posts = Vec::with_capacity(items.len());
+        for (env, adj) in items {
+            if let Some(prior) = self.applied(&adj.id) {
+                posts.push(prior.post_envelope.clone());
+                continue;
+            }
+            let post = project_adjustment(&env, &adj);
+            self.record(HiveLedgerEvent {
+                adjustment: adj,
+                pre_envelope: env,
+                post_envelope: post.clone(),
+            });
+            posts.push(post);
+        }
+        Ok(posts)
+    }
+
+    /// Like `apply_adjustment`, but reports the field deltas and any band transition.
+    pub fn apply_adjustment_detailed(
+        &mut self,
+        env: HiveEnvelope,
+        adj: HiveSystemAdjustment,
+    ) -> Result<HiveAdjustmentOutcome, HiveGuardError> {
+        let pre_temp = env.hive_temperature_c;
+        let pre_radius = env.forage_radius_m;
+        let pre_eco = env.eco_impact_score_corridor;
+        let pre_band = env.evaluate_band();
+
+        let post = self.apply_adjustment(env, adj)?;
+        let post_band = post.evaluate_band();
+        Ok(HiveAdjustmentOutcome {
+            delta_temp_c: post.hive_temperature_c - pre_temp,
+            delta_forage_radius_m: post.forage_radius_m - pre_radius,
+            delta_eco_impact: post.eco_impact_score_corridor - pre_eco,
+            band_changed: (pre_band != post_band).then_some((pre_band, post_band)),
+            post,
+        })
+    }
+}
+
+/// Envelope after applying `adj` to `env`, assuming all invariants already pass.
+fn project_adjustment(env: &HiveEnvelope, adj: &HiveSystemAdjustment) -> HiveEnvelope {
+    let mut post = env.clone();
+    post.ambient_toxin_ppb += adj.delta_pesticide_exposure_ppb;
+    post.forage_radius_m += adj.delta_forage_radius_m;
+    post.forage_diversity_index =
+        (post.forage_diversity_index + adj.delta_forage_diversity_index).clamp(0.0, 1.0);
+    post.hive_temperature_c += projected_temp_delta(env, adj);
+    post.eco_impact_score_corridor += adj.delta_eco_impact_score_corridor;
+    post.eco_band = post.evaluate_band();
+    post
+}
+
+/// Validate a batch of (name, json) telemetry files before ingestion. Each file
+/// is deserialized, sanitized and checked with `HiveEnvelope::validate`
+/// independently; its result lists every problem found, in that order.
+pub fn validate_envelopes_json(
+    inputs: &[(String, String)],
+) -> Vec<(String, Result<(), Vec<String>>)> {
+    inputs
+        .iter()
+        .map(|(name, json)| {
+            let result = match serde_json::from_str::<HiveEnvelope>(json) {
+                Err(e) => Err(vec![format!("invalid JSON: {}", e)]),
+                Ok(env) => {
+                    let mut problems = env.sanitize();
+                    if let Err(e) = env.validate() {
+                        problems.push(e.to_string());
+                    }
+                    if problems.is_empty() {
+                        Ok(())
+                    } else {
+                        Err(problems)
+                    }
+                }
+            };
+            (name.clone(), result)
+        })
+        .collect()
+}
+
+/// Hives a landscape-level adjustment would protect: those within `radius_m` of
+/// the adjustment's target hive (planar positions in meters) for which the
+/// adjustment passes every invariant and carries a positive eco gain.
+pub fn protected_hives(
+    adj: &HiveSystemAdjustment,
+    hives: &[HiveEnvelope],
+    radius_m: f32,
+    positions: &HashMap<String, (f64, f64)>,
+) -> Vec<String> {
+    if adj.delta_eco_impact_score_corridor <= 0.0 {
+        return Vec::new();
+    }
+    let Some(&(ox, oy)) = positions.get(&adj.hive_id) else {
+        return Vec::new();
+    };
+    let r = radius_m as f64;
+
+    hives
+        .iter()
+        .filter(|h| {
+            positions
+                .get(&h.hive_id)
+                .is_some_and(|&(x, y)| (x - ox).powi(2) + (y - oy).powi(2) <= r * r)
+        })
+        .filter(|h| {
+            let mut local = adj.clone();
+            local.hive_id = h.hive_id.clone();
+            HiveInnerLedger::new().check_adjustment(h, &local).is_empty()
+        })
+        .map(|h| h.hive_id.clone())
+        .collect()
+}
+
+/// Upper end of the `eco_impact_score_corridor` scale used by `need_centroid`.
+pub const ECO_IMPACT_SCORE_MAX: f32 = 100.0;
+
+/// Centroid of hive positions weighted by unmet eco need, `1 - eco_impact / 100`
+/// (clamped to [0, 1]) using `effective_eco_impact_score`, so soft forager
+/// overload counts as need. Hives without a position are skipped. `None` when no hive
+/// has a position or every positioned hive has zero need.
+pub fn need_centroid(
+    hives: &[HiveEnvelope],
+    positions: &HashMap<String, (f64, f64)>,
+) -> Option<(f64, f64)> {
+    let (mut sx, mut sy, mut total) = (0.0, 0.0, 0.0);
+    for h in hives {
+        let Some(&(x, y)) = positions.get(&h.hive_id) else {
+            continue;
         };
-        self.events.push(event);
-        Ok(env)
+        let eco = (h.effective_eco_impact_score() / ECO_IMPACT_SCORE_MAX) as f64;
+        let need = (1.0 - eco).clamp(0.0, 1.0);
+        sx += need * x;
+        sy += need * y;
+        total += need;
     }
+    (total > 0.0).then(|| (sx / total, sy / total))
 }
 
-/// Simple model: more shade slightly reduces temperature.
-fn temp_delta_from_shade(delta_shade_fraction: f32) -> f32 {
-    // Shade in [0,1] -> up to -5C, but never heating.
-    let clamped = delta_shade_fraction.clamp(-1.0, 1.0);
-    if clamped >= 0.0 {
-        -5.0 * clamped
-    } else {
-        // Removing shade, bounded; still must not cross safe max (checked above).
-        2.5 * (-clamped)
+/// Eco-impact-score timeline of one hive as (unix seconds, post-event effective score).
+fn eco_timeline(ledger: &HiveInnerLedger, hive_id: &str) -> Vec<(f64, f64)> {
+    ledger
+        .events_for(hive_id)
+        .iter()
+        .map(|e| {
+            (
+                e.adjustment.timestamp.timestamp() as f64,
+                e.post_envelope.effective_eco_impact_score() as f64,
+            )
+        })
+        .collect()
+}
+
+/// Linear interpolation of a time-sorted timeline at `t` (held constant past the ends).
+fn sample_at(timeline: &[(f64, f64)], t: f64) -> f64 {
+    match timeline.iter().position(|(ti, _)| *ti >= t) {
+        Some(0) => timeline[0].1,
+        Some(i) => {
+            let (t0, v0) = timeline[i - 1];
+            let (t1, v1) = timeline[i];
+            if t1 == t0 {
+                v1
+            } else {
+                v0 + (v1 - v0) * (t - t0) / (t1 - t0)
+            }
+        }
+        None => timeline[timeline.len() - 1].1,
     }
 }
 
-/// Risk envelope classification for external callers.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum RiskEnvelope {
-    Safe,
-    Warning,
-    Critical,
+/// `evaluate_band` change caused by one ledger event.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BandTransition {
+    pub adjustment_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub from: EcoBand,
+    pub to: EcoBand,
 }
 
-pub fn classify_risk(env: &HiveEnvelope) -> RiskEnvelope {
-    match env.evaluate_band() {
-        EcoBand::Safe => RiskEnvelope::Safe,
-        EcoBand::Warning => RiskEnvelope::Warning,
-        EcoBand::Critical => RiskEnvelope::Critical,
+/// Self-contained regulator export of one hive, from `audit_bundle`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditBundle {
+    pub hive_id: String,
+    pub current: HiveEnvelope,
+    /// The hive's accepted events in timestamp order.
+    pub events: Vec<HiveLedgerEvent>,
+    pub band_transitions: Vec<BandTransition>,
+    /// Total pesticide exposure removed by the events (positive ppb).
+    pub cumulative_pesticide_reduction_ppb: f32,
+    /// FNV-1a over the current envelope and each event's id, timestamp and
+    /// pre/post `content_hash`; stable across runs and platforms.
+    pub content_hash: u64,
+}
+
+/// Bundle `current`, the ledger's history for `hive_id`, its band transitions
+/// and cumulative pesticide reduction into one hashed, serializable artifact.
+pub fn audit_bundle(
+    ledger: &HiveInnerLedger,
+    hive_id: &str,
+    current: &HiveEnvelope,
+) -> AuditBundle {
+    let events: Vec<HiveLedgerEvent> = ledger.events_for(hive_id).into_iter().cloned().collect();
+    let band_transitions = events
+        .iter()
+        .filter_map(|e| {
+            let (from, to) = (e.pre_envelope.evaluate_band(), e.post_envelope.evaluate_band());
+            (from != to).then(|| BandTransition {
+                adjustment_id: e.adjustment.id.clone(),
+                timestamp: e.adjustment.timestamp,
+                from,
+                to,
+            })
+        })
+        .collect();
+    let cumulative_pesticide_reduction_ppb = -events
+        .iter()
+        .map(|e| e.adjustment.delta_pesticide_exposure_ppb)
+        .sum::<f32>();
+
+    let mut h = fnv1a(FNV_OFFSET, hive_id.as_bytes());
+    h = fnv1a(h, &current.content_hash().to_le_bytes());
+    for e in &events {
+        h = fnv1a(h, e.adjustment.id.as_bytes());
+        h = fnv1a(h, &[0xff]);
+        h = fnv1a(h, &e.adjustment.timestamp.timestamp_micros().to_le_bytes());
+        h = fnv1a(h, &e.pre_envelope.content_hash().to_le_bytes());
+        h = fnv1a(h, &e.post_envelope.content_hash().to_le_bytes());
+    }
+
+    AuditBundle {
+        hive_id: hive_id.to_string(),
+        current: current.clone(),
+        events,
+        band_transitions,
+        cumulative_pesticide_reduction_ppb,
+        content_hash: h,
+    }
+}
+
+/// Pearson correlation of two hives' eco-impact-score timelines.
+///
+/// Both timelines are resampled by linear interpolation onto the union of their
+/// event timestamps within the overlapping time range. Returns 0.0 when there is
+/// no overlap, fewer than two aligned samples, or zero variance.
+pub fn band_correlation(ledger: &HiveInnerLedger, hive_a: &str, hive_b: &str) -> f64 {
+    let a = eco_timeline(ledger, hive_a);
+    let b = eco_timeline(ledger, hive_b);
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let start = a[0].0.max(b[0].0);
+    let end = a[a.len() - 1].0.min(b[b.len() - 1].0);
+
+    let mut times: Vec<f64> = a
+        .iter()
+        .chain(b.iter())
+        .map(|(t, _)| *t)
+        .filter(|t| *t >= start && *t <= end)
+        .collect();
+    times.sort_by(|x, y| x.total_cmp(y));
+    times.dedup();
+    if times.len() < 2 {
+        return 0.0;
+    }
+
+    let xs: Vec<f64> = times.iter().map(|t| sample_at(&a, *t)).collect();
+    let ys: Vec<f64> = times.iter().map(|t| sample_at(&b, *t)).collect();
+    pearson(&xs, &ys)
+}
+
+/// Pearson correlation of paired samples; 0.0 when either side has zero variance.
+fn pearson(xs: &[f64], ys: &[f64]) -> f64 {
+    let n = xs.len() as f64;
+    let mx = xs.iter().sum::<f64>() / n;
+    let my = ys.iter().sum::<f64>() / n;
+    let mut cov = 0.0;
+    let mut vx = 0.0;
+    let mut vy = 0.0;
+    for (x, y) in xs.iter().zip(ys) {
+        cov += (x - mx) * (y - my);
+        vx += (x - mx) * (x - mx);
+        vy += (y - my) * (y - my);
+    }
+    if vx <= 0.0 || vy <= 0.0 {
+        return 0.0;
+    }
+    cov / (vx.sqrt() * vy.sqrt())
+}
+
+/// Trend of a hive's eco-impact score: the least-squares slope, in score points
+/// per hour, of its timeline over the `window` ending at its latest event.
+/// Positive means improving. Returns 0.0 with fewer than two points in the
+/// window or when they all share one timestamp.
+pub fn eco_impact_rate(ledger: &HiveInnerLedger, hive_id: &str, window: Duration) -> f32 {
+    let timeline = eco_timeline(ledger, hive_id);
+    let Some(&(latest, _)) = timeline.last() else {
+        return 0.0;
+    };
+    let since = latest - window.num_seconds() as f64;
+    let recent: Vec<(f64, f64)> = timeline
+        .into_iter()
+        .filter(|(t, _)| *t >= since)
+        .map(|(t, v)| ((t - latest) / 3600.0, v))
+        .collect();
+    if recent.len() < 2 {
+        return 0.0;
+    }
+    let n = recent.len() as f64;
+    let mt = recent.iter().map(|(t, _)| t).sum::<f64>() / n;
+    let mv = recent.iter().map(|(_, v)| v).sum::<f64>() / n;
+    let (mut cov, mut var) = (0.0, 0.0);
+    for (t, v) in &recent {
+        cov += (t - mt) * (v - mv);
+        var += (t - mt) * (t - mt);
+    }
+    if var <= 0.0 {
+        return 0.0;
+    }
+    (cov / var) as f32
+}
+
+/// Whether Critical hives got more help: the Pearson correlation across `hives`
+/// between hours spent Critical and the number of adjustments each received, in
+/// [-1, 1] (higher = needier hives got more help).
+///
+/// Time is measured over the ledger's span, from its earliest to its latest event.
+/// Before a hive's first event it is in that event's pre-envelope band; each
+/// post-envelope then holds until the hive's next event or the span's end. A hive
+/// with no events sits in its band from `hives` for the whole span. Returns 0.0
+/// with fewer than two hives, an empty ledger, or no variation on either side.
+pub fn scheduling_fairness(ledger: &HiveInnerLedger, hives: &[HiveEnvelope]) -> f64 {
+    let stamps = ledger.events().iter().map(|e| e.adjustment.timestamp);
+    let (Some(start), Some(end)) = (stamps.clone().min(), stamps.max()) else {
+        return 0.0;
+    };
+    let hours = |from: DateTime<Utc>, to: DateTime<Utc>| (to - from).num_seconds() as f64 / 3600.0;
+    let critical = |env: &HiveEnvelope| env.evaluate_band() == EcoBand::Critical;
+
+    let (mut need, mut help) = (Vec::new(), Vec::new());
+    for hive in hives {
+        let events = ledger.events_for(&hive.hive_id);
+        let critical_hours = match (events.first(), events.last()) {
+            (Some(first), Some(last)) => {
+                let mut total = 0.0;
+                if critical(&first.pre_envelope) {
+                    total += hours(start, first.adjustment.timestamp);
+                }
+                for pair in events.windows(2) {
+                    if critical(&pair[0].post_envelope) {
+                        total += hours(pair[0].adjustment.timestamp, pair[1].adjustment.timestamp);
+                    }
+                }
+                if critical(&last.post_envelope) {
+                    total += hours(last.adjustment.timestamp, end);
+                }
+                total
+            }
+            _ if critical(hive) => hours(start, end),
+            _ => 0.0,
+        };
+        need.push(critical_hours);
+        help.push(events.len() as f64);
+    }
+    if hives.len() < 2 {
+        return 0.0;
+    }
+    pearson(&need, &help)
+}
+
+/// Hazard per hour spent in the Critical band.
+pub const CRITICAL_HAZARD_PER_HOUR: f64 = 0.01;
+/// Hazard per hour per unit of normalized overshoot past the safe bounds.
+pub const OVERSHOOT_HAZARD_PER_HOUR: f64 = 0.05;
+
+/// Normalized distance of an envelope outside its safe bounds (0 when Safe).
+/// Each metric contributes its excess relative to the bound it crosses.
+fn overshoot(env: &HiveEnvelope) -> f64 {
+    let (temp, toxin, diversity, radius) = safe_band_distances(env);
+    (temp + toxin + diversity + radius) as f64
+}
+
+/// Unbounded distances of (temperature, toxin, diversity, radius) outside their
+/// safe bounds, each normalized by the band width or limit; 0.0 when in band.
+/// Toxin is the worst of the aggregate and each species against its own limit.
+fn safe_band_distances(env: &HiveEnvelope) -> (f32, f32, f32, f32) {
+    let band_width = (env.safe_temperature_c_max - env.safe_temperature_c_min).max(1e-6);
+    let temp = if env.hive_temperature_c > env.safe_temperature_c_max {
+        (env.hive_temperature_c - env.safe_temperature_c_max) / band_width
+    } else if env.hive_temperature_c < env.safe_temperature_c_min {
+        (env.safe_temperature_c_min - env.hive_temperature_c) / band_width
+    } else {
+        0.0
+    };
+    let excess = |ppb: f32, max: f32| (ppb - max).max(0.0) / max.max(1e-6);
+    let toxin = env
+        .toxins
+        .iter()
+        .map(|(species, ppb)| excess(*ppb, env.safe_toxin_max_for(species)))
+        .fold(excess(env.ambient_toxin_ppb, env.safe_toxin_ppb_max), f32::max);
+    let diversity = (env.safe_forage_diversity_index_min - env.forage_diversity_index).max(0.0)
+        / env.safe_forage_diversity_index_min.max(1e-6);
+    let radius = (env.safe_forage_radius_m_min - env.forage_radius_m).max(0.0)
+        / env.safe_forage_radius_m_min.max(1e-6);
+    (temp, toxin, diversity, radius)
+}
+
+/// Colony survival probability in [0,1] from a hive's ledger history, with the
+/// last event's state held until now. See `survival_estimate_at`.
+pub fn survival_estimate(ledger: &HiveInnerLedger, hive_id: &str) -> f64 {
+    survival_estimate_at(ledger, hive_id, Utc::now())
+}
+
+/// `survival_estimate` evaluated at a fixed `as_of` instead of the wall clock.
+///
+/// Each event's post-envelope is held until the hive's next event, and the last
+/// one until `as_of` (no tail if `as_of` is not after it). Over those intervals
+/// the cumulative hazard is
+/// `H = CRITICAL_HAZARD_PER_HOUR * hours_in_critical + OVERSHOOT_HAZARD_PER_HOUR * ∫ overshoot dt`,
+/// and survival is `exp(-H)`. A hive with no history has survival 1.0.
+pub fn survival_estimate_at(
+    ledger: &HiveInnerLedger,
+    hive_id: &str,
+    as_of: DateTime<Utc>,
+) -> f64 {
+    let events = ledger.events_for(hive_id);
+    let ends = events
+        .iter()
+        .skip(1)
+        .map(|e| e.adjustment.timestamp)
+        .chain(std::iter::once(as_of));
+    let mut hazard = 0.0;
+    for (event, end) in events.iter().zip(ends) {
+        let secs = (end - event.adjustment.timestamp).num_seconds().max(0);
+        let hours = secs as f64 / 3600.0;
+        let env = &event.post_envelope;
+        if env.evaluate_band() == EcoBand::Critical {
+            hazard += CRITICAL_HAZARD_PER_HOUR * hours;
+        }
+        hazard += OVERSHOOT_HAZARD_PER_HOUR * overshoot(env) * hours;
+    }
+    (-hazard).exp().clamp(0.0, 1.0)
+}
+
+/// Shrink a residual timeline to about `target_len` samples for archiving without
+/// losing breaches: every sample above `hard` is kept, and the remaining slots are
+/// filled by evenly spaced picks (including both ends) from the non-breach samples.
+/// Order is preserved. If breaches alone exceed `target_len`, all of them are kept.
+pub fn downsample_preserving_breaches(
+    timeline: &[(DateTime<Utc>, f64)],
+    target_len: usize,
+    hard: f64,
+) -> Vec<(DateTime<Utc>, f64)> {
+    if timeline.len() <= target_len {
+        return timeline.to_vec();
+    }
+    let (breach, calm): (Vec<usize>, Vec<usize>) =
+        (0..timeline.len()).partition(|&i| timeline[i].1 > hard);
+    let slots = target_len.saturating_sub(breach.len()).min(calm.len());
+
+    let mut keep = breach;
+    match slots {
+        0 => {}
+        1 => keep.push(calm[0]),
+        _ => keep.extend((0..slots).map(|k| calm[k * (calm.len() - 1) / (slots - 1)])),
+    }
+    keep.sort_unstable();
+    keep.dedup();
+    keep.into_iter().map(|i| timeline[i]).collect()
+}
+
+/// Hours until first-order decay of ambient and per-species toxins alone brings
+/// `env` to Safe, with toxin(t) = toxin_0 * 0.5^(t / half_life_hours); the
+/// slowest species to reach its own limit sets the time. `Some(0.0)` if already
+/// Safe; `None` if temperature or forage also breach, the half-life is not
+/// positive, a breaching toxin has a non-positive limit, or recovery would take
+/// longer than `max_hours`.
+pub fn forecast_band_recovery(env: &HiveEnvelope, half_life_hours: f32, max_hours: f32) -> Option<f32> {
+    if env.evaluate_band() == EcoBand::Safe {
+        return Some(0.0);
+    }
+    // Every toxin at most at its limit isolates whether anything else is breaching.
+    let mut detoxed = env.clone();
+    detoxed.ambient_toxin_ppb = env.ambient_toxin_ppb.min(env.safe_toxin_ppb_max);
+    for (species, ppb) in &mut detoxed.toxins {
+        *ppb = ppb.min(env.safe_toxin_max_for(species));
+    }
+    if detoxed.evaluate_band() != EcoBand::Safe || half_life_hours <= 0.0 {
+        return None;
+    }
+    let levels = env
+        .toxins
+        .iter()
+        .map(|(species, ppb)| (*ppb, env.safe_toxin_max_for(species)))
+        .chain(std::iter::once((env.ambient_toxin_ppb, env.safe_toxin_ppb_max)))
+        .filter(|(ppb, max)| ppb > max);
+    let mut hours = 0.0f32;
+    for (ppb, max) in levels {
+        if max <= 0.0 {
+            return None;
+        }
+        hours = hours.max(half_life_hours * (ppb / max).log2());
+    }
+    (hours <= max_hours).then_some(hours)
+}
+
+/// Passive per-step dynamics of a hive with no intervention, for `baseline_drift`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DriftModel {
+    /// Half-life of ambient and per-species toxins, in steps; 0 disables decay.
+    pub toxin_half_life_steps: f32,
+    /// Seasonal hive temperature trend (C per step, signed).
+    pub temp_trend_c_per_step: f32,
+    /// Forage diversity lost per step to habitat degradation (floored at 0).
+    pub diversity_loss_per_step: f32,
+    /// Forage radius lost per step, in meters (floored at 0).
+    pub forage_radius_loss_m_per_step: f32,
+}
+
+/// Counterfactual trajectory of `env` under `drift` alone: each step decays toxins
+/// by first-order kinetics, shifts temperature by the seasonal trend, erodes forage
+/// diversity and radius, then refreshes `eco_band`. Returns the `steps` envelopes
+/// after each step, excluding the starting one.
+pub fn baseline_drift(env: HiveEnvelope, drift: &DriftModel, steps: usize) -> Vec<HiveEnvelope> {
+    let decay = if drift.toxin_half_life_steps > 0.0 {
+        0.5f32.powf(1.0 / drift.toxin_half_life_steps)
+    } else {
+        1.0
+    };
+    let mut current = env;
+    let mut out = Vec::with_capacity(steps);
+    for _ in 0..steps {
+        current.ambient_toxin_ppb *= decay;
+        for (_, ppb) in &mut current.toxins {
+            *ppb *= decay;
+        }
+        current.hive_temperature_c += drift.temp_trend_c_per_step;
+        current.forage_diversity_index =
+            (current.forage_diversity_index - drift.diversity_loss_per_step).max(0.0);
+        current.forage_radius_m =
+            (current.forage_radius_m - drift.forage_radius_loss_m_per_step).max(0.0);
+        current.eco_band = current.evaluate_band();
+        out.push(current.clone());
+    }
+    out
+}
+
+/// Inclusive sweep over an axis: (start, end, number of samples).
+pub type SweepRange = (f32, f32, usize);
+
+fn sweep_values((start, end, n): SweepRange) -> Vec<f32> {
+    match n {
+        0 => Vec::new(),
+        1 => vec![start],
+        _ => (0..n)
+            .map(|i| start + (end - start) * i as f32 / (n - 1) as f32)
+            .collect(),
+    }
+}
+
+/// What-if grid of bands: row `i` sets `hive_temperature_c` to the i-th sample of
+/// `temp_range` and column `j` sets `ambient_toxin_ppb` to the j-th sample of
+/// `toxin_range`, all other fields taken from `base`. Samples are evenly spaced
+/// and include both ends.
+pub fn band_matrix(
+    base: &HiveEnvelope,
+    temp_range: SweepRange,
+    toxin_range: SweepRange,
+) -> Vec<Vec<EcoBand>> {
+    let toxins = sweep_values(toxin_range);
+    sweep_values(temp_range)
+        .into_iter()
+        .map(|temp| {
+            toxins
+                .iter()
+                .map(|&toxin| {
+                    let mut env = base.clone();
+                    env.hive_temperature_c = temp;
+                    env.ambient_toxin_ppb = toxin;
+                    env.evaluate_band()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+type MetricFn = fn(&HiveEnvelope) -> f32;
+
+/// Per-metric z-score of `current` against the hive's own history: how many
+/// standard deviations each value sits from the mean of the post-adjustment
+/// envelopes recorded for `hive_id`. A metric that never varied yields 0.0 when
+/// unchanged and a signed infinity otherwise. Empty when there is no history.
+pub fn baseline_deviation(
+    ledger: &HiveInnerLedger,
+    hive_id: &str,
+    current: &HiveEnvelope,
+) -> HashMap<&'static str, f32> {
+    let history: Vec<&HiveEnvelope> = ledger
+        .events_for(hive_id)
+        .into_iter()
+        .map(|e| &e.post_envelope)
+        .collect();
+    if history.is_empty() {
+        return HashMap::new();
+    }
+
+    let metrics: [(&'static str, MetricFn); 8] = [
+        ("hive_temperature_c", |e| e.hive_temperature_c),
+        ("ambient_toxin_ppb", |e| e.ambient_toxin_ppb),
+        ("forage_diversity_index", |e| e.forage_diversity_index),
+        ("forage_radius_m", |e| e.forage_radius_m),
+        ("forager_load", |e| e.forager_load),
+        ("nectar_kg", |e| e.nectar_kg),
+        ("pollen_kg", |e| e.pollen_kg),
+        ("eco_impact_score_corridor", |e| e.eco_impact_score_corridor),
+    ];
+    let n = history.len() as f32;
+    metrics
+        .iter()
+        .map(|(name, get)| {
+            let mean = history.iter().map(|e| get(e)).sum::<f32>() / n;
+            let var = history.iter().map(|e| (get(e) - mean).powi(2)).sum::<f32>() / n;
+            let diff = get(current) - mean;
+            let z = if var > 0.0 {
+                diff / var.sqrt()
+            } else if diff == 0.0 {
+                0.0
+            } else {
+                f32::INFINITY.copysign(diff)
+            };
+            (*name, z)
+        })
+        .collect()
+}
+
+/// Maximum evaporative cooling from added water availability (C).
+const WATER_COOLING_MAX_C: f32 = 2.0;
+
+/// Projected hive temperature change from an adjustment's microclimate deltas:
+/// the shade term (scaled by the hive's `shade_cooling_coeff_c`) plus evaporative
+/// cooling from positive water availability deltas, bounded at
+/// `WATER_COOLING_MAX_C`. Removing water adds no heating here.
+pub fn projected_temp_delta(env: &HiveEnvelope, adj: &HiveSystemAdjustment) -> f32 {
+    let water_cooling = -WATER_COOLING_MAX_C * adj.delta_water_availability_index.clamp(0.0, 1.0);
+    temp_delta_from_shade(adj.delta_shade_fraction, env.shade_cooling_coeff_c) + water_cooling
+}
+
+/// Resolution of `binding_invariant`'s probe over perturbation sizes in (0, 1].
+pub const BINDING_PROBE_STEPS: u32 = 100;
+
+/// The state-dependent invariant closest to binding for `env`: probes worsening
+/// perturbations of growing size `e` (removing a fraction `e` of full shade, and
+/// shrinking forage radius by a fraction `e`) and returns the error that
+/// `check_adjustment` raises first. Sign-only invariants (pesticide, light/noise,
+/// eco score) bind at any worsening regardless of state, so they are not probed.
+/// `None` if neither trips even at `e = 1`.
+pub fn binding_invariant(env: &HiveEnvelope) -> Option<HiveGuardError> {
+    let ledger = HiveInnerLedger::new();
+    let probe = |adj: HiveSystemAdjustment, target: HiveGuardError| {
+        ledger.check_adjustment(env, &adj).contains(&target).then_some(target)
+    };
+    (1..=BINDING_PROBE_STEPS).find_map(|k| {
+        let e = k as f32 / BINDING_PROBE_STEPS as f32;
+        let base = HiveSystemAdjustment {
+            id: format!("probe-{}-{}", env.hive_id, k),
+            timestamp: Utc::now(),
+            hive_id: env.hive_id.clone(),
+            delta_pesticide_exposure_ppb: 0.0,
+            delta_shade_fraction: 0.0,
+            delta_water_availability_index: 0.0,
+            delta_forage_radius_m: 0.0,
+            delta_forage_diversity_index: 0.0,
+            delta_artificial_light_nits: 0.0,
+            delta_noise_db: 0.0,
+            delta_eco_impact_score_corridor: 0.0,
+        };
+        let unshade = HiveSystemAdjustment { delta_shade_fraction: -e, ..base.clone() };
+        let shrink = HiveSystemAdjustment {
+            delta_forage_radius_m: -e * env.forage_radius_m,
+            ..base
+        };
+        probe(unshade, HiveGuardError::RaisesHiveTemperature)
+            .or_else(|| probe(shrink, HiveGuardError::ReducesForageRadius))
+    })
+}
+
+/// Simple model: more shade slightly reduces temperature.
+fn temp_delta_from_shade(delta_shade_fraction: f32, coeff_c: f32) -> f32 {
+    // Shade in [0,1] -> up to -coeff_c, but never heating (negative coefficients clamp to 0).
+    let coeff_c = coeff_c.max(0.0);
+    let clamped = delta_shade_fraction.clamp(-1.0, 1.0);
+    if clamped >= 0.0 {
+        -coeff_c * clamped
+    } else {
+        // Removing shade, bounded; still must not cross safe max (checked above).
+        0.5 * coeff_c * (-clamped)
+    }
+}
+
+/// Risk envelope classification for external callers.
+/// Ordered like `EcoBand`: higher means worse.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RiskEnvelope {
+    Safe,
+    Warning,
+    Recovering,
+    Critical,
+}
+
+pub fn classify_risk(env: &HiveEnvelope) -> RiskEnvelope {
+    match env.evaluate_band() {
+        EcoBand::Safe => RiskEnvelope::Safe,
+        EcoBand::Warning => RiskEnvelope::Warning,
+        EcoBand::Recovering => RiskEnvelope::Recovering,
+        EcoBand::Critical => RiskEnvelope::Critical,
+    }
+}
+
+
+impl HiveEnvelope {
+    /// Map the flat envelope onto the risk-coordinate model used by `hive::evaluate_hive`.
+    /// Fields the flat model lacks (brood temperature, humidity, wax toxins, region)
+    /// fall back to the hive temperature or neutral zero values.
+    pub fn to_core_envelope(&self) -> hive::HiveEnvelope {
+        hive::HiveEnvelope {
+            hive_id: self.hive_id.clone(),
+            region: String::new(),
+            brood_temp_c: self.hive_temperature_c as f64,
+            hive_temp_c: self.hive_temperature_c as f64,
+            hive_humidity_pct: 0.0,
+            nectar_kg: self.nectar_kg as f64,
+            pollen_kg: self.pollen_kg as f64,
+            forager_load_pct: self.forager_load as f64,
+            toxin_index_air: self.ambient_toxin_ppb as f64,
+            toxin_index_wax: 0.0,
+            forage_radius_km: self.forage_radius_m as f64 / 1000.0,
+            eco_band: match self.eco_band {
+                EcoBand::Safe => hive::EcoBand::Safe,
+                EcoBand::Warning | EcoBand::Recovering => hive::EcoBand::Warning,
+                EcoBand::Critical => hive::EcoBand::Critical,
+            },
+        }
+    }
+}
+
+impl From<&HiveEnvelope> for hive::HiveEnvelope {
+    fn from(env: &HiveEnvelope) -> Self {
+        env.to_core_envelope()
+    }
+}
+
+/// Immutable, cheaply clonable snapshot of a HiveEnvelope for shared monitoring.
+/// Only read-only evaluation is exposed, so no thread can mutate the envelope.
+#[derive(Debug, Clone)]
+pub struct FrozenHive(Arc<HiveEnvelope>);
+
+impl FrozenHive {
+    pub fn new(env: HiveEnvelope) -> Self {
+        Self(Arc::new(env))
+    }
+
+    pub fn envelope(&self) -> &HiveEnvelope {
+        &self.0
+    }
+
+    pub fn band(&self) -> EcoBand {
+        self.0.evaluate_band()
+    }
+
+    pub fn classify(&self) -> RiskEnvelope {
+        classify_risk(&self.0)
+    }
+
+    pub fn residual(&self, corridors: &hive::HiveCorridors) -> risk::Residual {
+        hive::evaluate_hive(&self.0.to_core_envelope(), corridors)
+    }
+}
+
+impl From<HiveEnvelope> for FrozenHive {
+    fn from(env: HiveEnvelope) -> Self {
+        Self::new(env)
+    }
+}
+
+/// Band implied by a risk-coordinate residual: stop → Critical, derate → Warning.
+pub fn band_from_residual(res: &risk::Residual) -> EcoBand {
+    if res.stop {
+        EcoBand::Critical
+    } else if res.derate {
+        EcoBand::Warning
+    } else {
+        EcoBand::Safe
+    }
+}
+
+/// Verdict reconciling the flat `evaluate_band` with the corridor `evaluate_hive` path.
+#[derive(Debug, Clone)]
+pub struct UnifiedVerdict {
+    /// Canonical band: the more severe of the two paths.
+    pub band: EcoBand,
+    pub flat_band: EcoBand,
+    pub corridor_band: EcoBand,
+    /// Set when the two paths disagree, describing both verdicts.
+    pub disagreement: Option<String>,
+}
+
+/// Evaluate a hive through both band models and return one canonical verdict.
+pub fn unified_evaluate(env: &HiveEnvelope, corridors: &hive::HiveCorridors) -> UnifiedVerdict {
+    let flat_band = env.evaluate_band();
+    let corridor_band = band_from_residual(&hive::evaluate_hive(&env.to_core_envelope(), corridors));
+    let band = corridor_band.max(flat_band);
+    let disagreement = (flat_band != corridor_band).then(|| {
+        format!(
+            "hive {}: flat band {:?} disagrees with corridor band {:?}",
+            env.hive_id, flat_band, corridor_band
+        )
+    });
+    UnifiedVerdict {
+        band,
+        flat_band,
+        corridor_band,
+        disagreement,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::bands::CorridorBands;
+    use super::hive::HiveCorridors;
+    use super::*;
+
+    fn sample_envelope() -> HiveEnvelope {
+        HiveEnvelope {
+            hive_id: "hive-alpha".into(),
+            brood_frames: 8,
+            nectar_kg: 12.0,
+            pollen_kg: 4.5,
+            hive_temperature_c: 34.0,
+            forager_load: 0.7,
+            ambient_toxin_ppb: 20.0,
+            forage_diversity_index: 0.8,
+            forage_radius_m: 1500.0,
+            eco_band: EcoBand::Safe,
+            eco_impact_score_corridor: 75.0,
+            safe_temperature_c_min: 32.0,
+            safe_temperature_c_max: 36.0,
+            safe_toxin_ppb_max: 50.0,
+            safe_forage_diversity_index_min: 0.5,
+            safe_forage_radius_m_min: 1000.0,
+            recovery_eco_threshold: None,
+            toxins: Vec::new(),
+            safe_toxin_ppb_max_by_species: HashMap::new(),
+            shade_cooling_coeff_c: DEFAULT_SHADE_COOLING_COEFF_C,
+            safe_forager_load_max: None,
+            forager_load_policy: ForagerLoadPolicy::HardBand,
+        }
+    }
+
+    fn sample_corridors() -> HiveCorridors {
+        HiveCorridors {
+            temp_bands: CorridorBands::new("hive_temp_c", "C", 35.0, 36.0, 38.0, 1.0, 0, true),
+            brood_temp_bands: CorridorBands::new("brood_temp_c", "C", 35.0, 35.5, 37.0, 1.0, 1, true),
+            humidity_bands: CorridorBands::new("hive_humidity_pct", "%", 70.0, 80.0, 95.0, 0.5, 2, true),
+            toxin_air_bands: CorridorBands::new("toxin_index_air", "ppb", 25.0, 40.0, 60.0, 2.0, 3, true),
+            toxin_wax_bands: CorridorBands::new("toxin_index_wax", "ppb", 10.0, 20.0, 40.0, 1.0, 4, true),
+            forage_radius_bands: CorridorBands::new("forage_radius_km", "km", 3.0, 4.0, 6.0, 0.5, 5, true),
+            forager_load_bands: CorridorBands::new("forager_load_pct", "fraction", 0.8, 0.9, 1.0, 0.5, 6, true),
+        }
+    }
+
+    #[test]
+    fn test_frozen_hive_shared_across_threads() {
+        let frozen = FrozenHive::new(sample_envelope());
+        let corridors = sample_corridors();
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let hive = frozen.clone();
+                let corridors = corridors.clone();
+                std::thread::spawn(move || (hive.band(), hive.residual(&corridors).stop))
+            })
+            .collect();
+
+        for h in handles {
+            let (band, stop) = h.join().unwrap();
+            assert_eq!(band, EcoBand::Safe);
+            assert!(!stop);
+        }
+        assert!(matches!(frozen.classify(), RiskEnvelope::Safe));
+    }
+
+    #[test]
+    fn test_safety_cost_barrier_past_hard_limit() {
+        let corridors = sample_corridors();
+        let mut env = sample_envelope().to_core_envelope();
+
+        env.hive_temp_c = 37.98;
+        let c1 = hive::safety_cost(&env, &corridors);
+        env.hive_temp_c = 37.99;
+        let c2 = hive::safety_cost(&env, &corridors);
+        env.hive_temp_c = 38.0;
+        let at_hard = hive::safety_cost(&env, &corridors);
+        env.hive_temp_c = 38.01;
+        let c3 = hive::safety_cost(&env, &corridors);
+
+        // Small step below the hard limit → small change in cost.
+        assert!((c2 - c1).abs() < 0.01);
+        // Exactly at hard is not a breach (`is_violation`) → no barrier yet.
+        assert!(at_hard < hive::SAFETY_BARRIER);
+        assert!((at_hard - c2).abs() < 0.01);
+        // Past the hard limit → jump by the barrier.
+        assert!(c3 - at_hard >= hive::SAFETY_BARRIER);
+    }
+
+    fn sample_adjustment() -> HiveSystemAdjustment {
+        HiveSystemAdjustment {
+            id: "adj-hive-alpha-task-1".into(),
+            timestamp: Utc::now(),
+            hive_id: "hive-alpha".into(),
+            delta_pesticide_exposure_ppb: -10.0,
+            delta_shade_fraction: 0.0,
+            delta_water_availability_index: 0.0,
+            delta_forage_radius_m: 200.0,
+            delta_forage_diversity_index: 0.05,
+            delta_artificial_light_nits: 0.0,
+            delta_noise_db: 0.0,
+            delta_eco_impact_score_corridor: 5.0,
+        }
+    }
+
+    #[test]
+    fn test_try_apply_records_accepted_and_rejected() {
+        let mut ledger = HiveInnerLedger::new();
+        assert!(ledger.try_apply(sample_envelope(), sample_adjustment()).is_ok());
+
+        let mut noisy = sample_adjustment();
+        noisy.id = "adj-hive-alpha-task-2".into();
+        noisy.delta_noise_db = 3.0;
+        let err = ledger.try_apply(sample_envelope(), noisy).unwrap_err();
+
+        assert_eq!(ledger.events().len(), 1);
+        assert_eq!(ledger.rejections().len(), 1);
+        assert_eq!(ledger.rejections()[0].1, err);
+        assert_eq!(err, HiveGuardError::IncreasesLightOrNoise);
+    }
+
+    #[test]
+    fn test_resubmitted_adjustment_applies_once() {
+        let mut ledger = HiveInnerLedger::new();
+        let adj = sample_adjustment();
+        let post = ledger.apply_adjustment(sample_envelope(), adj.clone()).unwrap();
+        assert_eq!(post.ambient_toxin_ppb, 10.0);
+
+        // A retry, even against the already-updated envelope, changes nothing.
+        let replay = ledger.apply_adjustment(post.clone(), adj.clone()).unwrap();
+        assert_eq!(replay, post);
+        assert_eq!(ledger.events().len(), 1);
+
+        let mut reused = adj;
+        reused.delta_pesticide_exposure_ppb = -5.0;
+        assert_eq!(
+            ledger.apply_adjustment(post, reused).unwrap_err(),
+            HiveGuardError::DuplicateAdjustment("adj-hive-alpha-task-1".into())
+        );
+        assert_eq!(ledger.events().len(), 1);
+    }
+
+    #[test]
+    fn test_to_risk_lipschitz_matches_linear_slope() {
+        let bands = CorridorBands::new("toxin_index_air", "ppb", 10.0, 20.0, 30.0, 1.0, 3, true);
+        let slope = (risk::to_risk(20.0, &bands) - risk::to_risk(15.0, &bands)) / 5.0;
+        assert!((risk::to_risk_lipschitz(&bands) - slope).abs() < 1e-12);
+        assert!((risk::to_risk_lipschitz(&bands) - 0.05).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_unified_evaluate_flags_disagreement() {
+        let env = sample_envelope();
+        let mut corridors = sample_corridors();
+        assert!(unified_evaluate(&env, &corridors).disagreement.is_none());
+
+        // 20 ppb is inside the flat 50 ppb limit but past a 15 ppb corridor hard limit.
+        corridors.toxin_air_bands = CorridorBands::new("toxin_index_air", "ppb", 5.0, 10.0, 15.0, 2.0, 3, true);
+        let verdict = unified_evaluate(&env, &corridors);
+        assert_eq!(verdict.flat_band, EcoBand::Safe);
+        assert_eq!(verdict.corridor_band, EcoBand::Critical);
+        assert_eq!(verdict.band, EcoBand::Critical);
+        assert!(verdict.disagreement.is_some());
+    }
+
+    #[test]
+    fn test_reproduction_hint_names_noise_field() {
+        let env = sample_envelope();
+        let mut adj = sample_adjustment();
+        adj.delta_noise_db = 3.0;
+
+        let err = HiveInnerLedger::new()
+            .apply_adjustment(env.clone(), adj.clone())
+            .unwrap_err();
+        assert_eq!(
+            err.reproduction_hint(&env, &adj),
+            "delta_noise_db=+3.0 violates IncreasesLightOrNoise (must be <= 0)"
+        );
+    }
+
+    #[test]
+    fn test_ledger_json_round_trip() {
+        let mut ledger = HiveInnerLedger::new();
+        ledger.try_apply(sample_envelope(), sample_adjustment()).unwrap();
+        let mut noisy = sample_adjustment();
+        noisy.delta_noise_db = 3.0;
+        ledger.try_apply(sample_envelope(), noisy).unwrap_err();
+
+        let json = ledger.to_json().unwrap();
+        let restored = HiveInnerLedger::from_json(&json).unwrap();
+        assert_eq!(restored, ledger);
+        assert_eq!(
+            restored.events()[0].adjustment.timestamp,
+            ledger.events()[0].adjustment.timestamp
+        );
+        let rfc3339 = ledger.events()[0]
+            .adjustment
+            .timestamp
+            .to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true);
+        assert!(json.contains(&rfc3339));
+    }
+
+    #[test]
+    fn test_eco_band_orders_by_severity() {
+        assert!(EcoBand::Safe < EcoBand::Critical);
+        assert!(EcoBand::Safe < EcoBand::Warning && EcoBand::Warning < EcoBand::Critical);
+        assert!(RiskEnvelope::Warning < RiskEnvelope::Critical);
+    }
+
+    #[test]
+    fn test_content_hash_quantizes_floats() {
+        let env = sample_envelope();
+        let mut near = env.clone();
+        near.hive_temperature_c += 0.0001;
+        let mut far = env.clone();
+        far.hive_temperature_c += 0.01;
+
+        assert_eq!(env.content_hash(), near.content_hash());
+        assert_ne!(env.content_hash(), far.content_hash());
+    }
+
+    #[test]
+    fn test_content_hash_band_codes_survive_new_variants() {
+        // Codes predate `Recovering`, which sits mid-enum for severity ordering.
+        assert_eq!(EcoBand::Safe.hash_code(), 0);
+        assert_eq!(EcoBand::Warning.hash_code(), 1);
+        assert_eq!(EcoBand::Critical.hash_code(), 2);
+        assert_eq!(EcoBand::Recovering.hash_code(), 3);
+        assert_ne!(EcoBand::Critical as u8, EcoBand::Critical.hash_code());
+
+        let hashes: Vec<u64> =
+            [EcoBand::Safe, EcoBand::Warning, EcoBand::Recovering, EcoBand::Critical]
+                .into_iter()
+                .map(|band| HiveEnvelope { eco_band: band, ..sample_envelope() }.content_hash())
+                .collect();
+        for (i, a) in hashes.iter().enumerate() {
+            assert!(hashes[i + 1..].iter().all(|b| a != b));
+        }
+    }
+
+    #[test]
+    fn test_to_core_envelope_maps_temperature_and_forage_radius() {
+        let env = sample_envelope();
+        let core = env.to_core_envelope();
+        assert_eq!(core.hive_id, env.hive_id);
+        assert_eq!(core.hive_temp_c, 34.0);
+        assert_eq!(core.brood_temp_c, 34.0);
+        assert!((core.forage_radius_km - 1.5).abs() < 1e-9);
+        assert_eq!(core.eco_band, hive::EcoBand::Safe);
+        assert!(core.region.is_empty());
+        assert_eq!(hive::HiveEnvelope::from(&env).toxin_index_air, 20.0);
+    }
+
+    #[test]
+    fn test_stress_attribution_toxin_driven_hive_is_landscape() {
+        let res = residual_of(vec![
+            coord("toxin_index_air", 0.9, 2.0),
+            coord("forage_radius_km", 0.2, 0.5),
+            coord("hive_temp_c", 0.1, 1.0),
+            coord("hive_humidity_pct", 0.1, 0.5),
+        ]);
+        let split = risk::stress_attribution(&res);
+        assert!(split.landscape > 0.8, "{:?}", split);
+        assert!(split.landscape > split.microclimate);
+        assert!((split.landscape + split.microclimate + split.other - 1.0).abs() < 1e-9);
+        assert_eq!(split.other, 0.0);
+    }
+
+    #[test]
+    fn test_apply_adjustment_detailed_reports_band_transition() {
+        let mut env = sample_envelope();
+        env.ambient_toxin_ppb = 55.0;
+        env.eco_band = env.evaluate_band();
+        assert_eq!(env.eco_band, EcoBand::Warning);
+
+        let mut ledger = HiveInnerLedger::new();
+        let outcome = ledger.apply_adjustment_detailed(env, sample_adjustment()).unwrap();
+        assert_eq!(outcome.band_changed, Some((EcoBand::Warning, EcoBand::Safe)));
+        assert_eq!(outcome.delta_forage_radius_m, 200.0);
+        assert_eq!(outcome.delta_eco_impact, 5.0);
+        assert_eq!(outcome.delta_temp_c, 0.0);
+        assert_eq!(ledger.events().len(), 1);
+
+        let mut next = sample_adjustment();
+        next.id = "adj-hive-alpha-task-2".into();
+        let again = ledger.apply_adjustment_detailed(outcome.post, next).unwrap();
+        assert!(again.band_changed.is_none());
+    }
+
+    #[test]
+    fn test_apply_batch_is_atomic() {
+        let mut noisy = sample_adjustment();
+        noisy.id = "adj-hive-alpha-noisy".into();
+        noisy.delta_noise_db = 3.0;
+        let mut ledger = HiveInnerLedger::new();
+
+        let err = ledger
+            .apply_batch(vec![
+                (sample_envelope(), sample_adjustment()),
+                (sample_envelope(), noisy),
+            ])
+            .unwrap_err();
+        assert_eq!(err, (1, HiveGuardError::IncreasesLightOrNoise));
+        assert!(ledger.events().is_empty());
+
+        let mut second = sample_adjustment();
+        second.id = "adj-hive-alpha-task-2".into();
+        let posts = ledger
+            .apply_batch(vec![
+                (sample_envelope(), sample_adjustment()),
+                (sample_envelope(), second.clone()),
+            ])
+            .unwrap();
+        assert_eq!(posts.len(), 2);
+        assert_eq!(ledger.events().len(), 2);
+
+        // An exact replay, in a batch or repeated within one, returns the recorded
+        // post-envelope without a new event.
+        let replay = ledger
+            .apply_batch(vec![
+                (sample_envelope(), second.clone()),
+                (sample_envelope(), second.clone()),
+            ])
+            .unwrap();
+        assert_eq!(replay, vec![posts[1].clone(), posts[1].clone()]);
+        assert_eq!(ledger.events().len(), 2);
+
+        // Reusing an applied id for different contents rejects the whole batch.
+        let mut third = sample_adjustment();
+        third.id = "adj-hive-alpha-task-3".into();
+        let mut altered = second;
+        altered.delta_forage_radius_m += 10.0;
+        let err = ledger
+            .apply_batch(vec![(sample_envelope(), third.clone()), (sample_envelope(), altered)])
+            .unwrap_err();
+        assert_eq!(err, (1, HiveGuardError::DuplicateAdjustment("adj-hive-alpha-task-2".into())));
+        assert_eq!(ledger.events().len(), 2);
+
+        // So does a repeated id within one batch whose contents differ.
+        let mut clash = third.clone();
+        clash.delta_noise_db = -2.0;
+        let err = ledger
+            .apply_batch(vec![(sample_envelope(), third), (sample_envelope(), clash)])
+            .unwrap_err();
+        assert_eq!(err, (1, HiveGuardError::DuplicateAdjustment("adj-hive-alpha-task-3".into())));
+        assert!(ledger.applied("adj-hive-alpha-task-3").is_none());
+    }
+
+    #[test]
+    fn test_ledger_id_index_survives_json_round_trip() {
+        let mut ledger = HiveInnerLedger::new();
+        let adj = sample_adjustment();
+        let post = ledger.apply_adjustment(sample_envelope(), adj.clone()).unwrap();
+        let mut restored = HiveInnerLedger::from_json(&ledger.to_json().unwrap()).unwrap();
+        assert_eq!(restored, ledger);
+
+        assert_eq!(restored.apply_adjustment(sample_envelope(), adj.clone()), Ok(post));
+        let mut altered = adj;
+        altered.delta_noise_db = -1.0;
+        assert_eq!(
+            restored.apply_adjustment(sample_envelope(), altered),
+            Err(HiveGuardError::DuplicateAdjustment("adj-hive-alpha-task-1".into()))
+        );
+        assert_eq!(restored.events().len(), 1);
+    }
+
+    #[test]
+    fn test_forecast_band_recovery_from_toxin_decay() {
+        let mut env = sample_envelope();
+        env.ambient_toxin_ppb = 100.0; // one half-life above the 50 ppb limit
+        let hours = forecast_band_recovery(&env, 12.0, 48.0).unwrap();
+        assert!((hours - 12.0).abs() < 1e-4);
+
+        env.ambient_toxin_ppb = 200.0;
+        assert!((forecast_band_recovery(&env, 12.0, 48.0).unwrap() - 24.0).abs() < 1e-4);
+        assert_eq!(forecast_band_recovery(&env, 12.0, 20.0), None);
+
+        env.forage_radius_m = 500.0;
+        assert_eq!(forecast_band_recovery(&env, 12.0, 48.0), None);
+        assert_eq!(forecast_band_recovery(&sample_envelope(), 12.0, 48.0), Some(0.0));
+    }
+
+    #[test]
+    fn test_species_toxins_count_in_weighted_band_and_recovery() {
+        let mut env = sample_envelope();
+        env.toxins = vec![("neonicotinoid".into(), 20.0)];
+        env.safe_toxin_ppb_max_by_species.insert("neonicotinoid".into(), 5.0);
+
+        // 20 ppb is under the 50 ppb aggregate limit but 3x the species limit.
+        let (band, score) = env.evaluate_band_weighted();
+        assert_eq!(band, EcoBand::Critical);
+        assert!((score - 1.0 / 3.0).abs() < 1e-6, "{}", score);
+
+        // Two half-lives take the species from 20 to its 5 ppb limit.
+        let hours = forecast_band_recovery(&env, 12.0, 48.0).unwrap();
+        assert!((hours - 24.0).abs() < 1e-4, "{}", hours);
+        env.ambient_toxin_ppb = 100.0;
+        let hours = forecast_band_recovery(&env, 12.0, 48.0).unwrap();
+        assert!((hours - 24.0).abs() < 1e-4, "{}", hours);
+
+        let drift = DriftModel {
+            toxin_half_life_steps: 1.0,
+            temp_trend_c_per_step: 0.0,
+            diversity_loss_per_step: 0.0,
+            forage_radius_loss_m_per_step: 0.0,
+        };
+        let path = baseline_drift(env, &drift, 2);
+        assert_ne!(path[0].eco_band, EcoBand::Safe);
+        assert_eq!(path[1].eco_band, EcoBand::Safe);
+        assert_eq!(path[1].evaluate_band_weighted().0, EcoBand::Safe);
+    }
+
+    #[test]
+    fn test_validate_rejects_inverted_band_and_bad_diversity() {
+        assert!(sample_envelope().validate().is_ok());
+
+        let mut inverted = sample_envelope();
+        inverted.safe_temperature_c_min = 37.0;
+        assert_eq!(
+            inverted.validate(),
+            Err(HiveEnvelopeError::InvertedTemperatureBand { min: 37.0, max: 36.0 })
+        );
+        let err = HiveInnerLedger::new()
+            .apply_adjustment(inverted, sample_adjustment())
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            HiveGuardError::InvalidEnvelope(HiveEnvelopeError::InvertedTemperatureBand { .. })
+        ));
+
+        let mut diverse = sample_envelope();
+        diverse.forage_diversity_index = 5.0;
+        assert!(matches!(
+            diverse.validate(),
+            Err(HiveEnvelopeError::DiversityOutOfRange { ref field, .. }) if field == "forage_diversity_index"
+        ));
+    }
+
+    #[test]
+    fn test_recovering_band_at_eco_threshold() {
+        let mut env = sample_envelope();
+        env.ambient_toxin_ppb = 55.0;
+        env.recovery_eco_threshold = Some(75.0);
+
+        // eco_impact_score_corridor == threshold: not yet above it.
+        assert_eq!(env.evaluate_band(), EcoBand::Warning);
+        env.eco_impact_score_corridor = 75.5;
+        assert_eq!(env.evaluate_band(), EcoBand::Recovering);
+        assert_eq!(classify_risk(&env), RiskEnvelope::Recovering);
+        assert!(EcoBand::Warning < EcoBand::Recovering && EcoBand::Recovering < EcoBand::Critical);
+
+        // Two breaches stay Warning regardless of eco impact.
+        env.forage_radius_m = 500.0;
+        assert_eq!(env.evaluate_band(), EcoBand::Warning);
+
+        env.forage_radius_m = 1500.0;
+        env.recovery_eco_threshold = None;
+        assert_eq!(env.evaluate_band(), EcoBand::Warning);
+    }
+
+    #[test]
+    fn test_downsample_preserves_single_breach() {
+        let t0 = DateTime::<Utc>::UNIX_EPOCH;
+        let timeline: Vec<(DateTime<Utc>, f64)> = (0..1000)
+            .map(|i| {
+                let v = if i == 517 { 1.4 } else { 0.2 };
+                (t0 + chrono::Duration::minutes(i), v)
+            })
+            .collect();
+
+        let down = downsample_preserving_breaches(&timeline, 20, 1.0);
+        assert_eq!(down.len(), 20);
+        assert!(down.contains(&timeline[517]));
+        assert_eq!(down.first(), timeline.first());
+        assert!(down.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn test_evaluate_band_weighted_thresholds() {
+        let mut env = sample_envelope();
+        assert_eq!(env.evaluate_band_weighted(), (EcoBand::Safe, 0.0));
+
+        // Toxin 98% over its 50 ppb limit: score 0.98 / 3 is just under 0.33, but
+        // the breach alone keeps the band at Warning, as in `evaluate_band`.
+        env.ambient_toxin_ppb = 99.0;
+        let (band, score) = env.evaluate_band_weighted();
+        assert_eq!(band, EcoBand::Warning);
+        assert!(score < 0.33 && score > 0.32, "{}", score);
+        assert_eq!(env.evaluate_band(), EcoBand::Warning);
+
+        // A fully saturated component is Critical even though the mean is 1/3.
+        env.ambient_toxin_ppb = 100.0;
+        let (band, score) = env.evaluate_band_weighted();
+        assert_eq!(band, EcoBand::Critical);
+        assert!((score - 1.0 / 3.0).abs() < 1e-6, "{}", score);
+
+        env.ambient_toxin_ppb = 50.0;
+        env.hive_temperature_c = 40.0;
+        assert_eq!(env.evaluate_band_weighted().0, EcoBand::Critical);
+    }
+
+    #[test]
+    fn test_evaluate_band_weighted_single_breach_is_never_safe() {
+        let base = sample_envelope();
+        let mut breaches = Vec::new();
+        let mut env = base.clone();
+        env.ambient_toxin_ppb = base.safe_toxin_ppb_max + 0.5;
+        breaches.push(env);
+        let mut env = base.clone();
+        env.hive_temperature_c = base.safe_temperature_c_max + 0.1;
+        breaches.push(env);
+        let mut env = base.clone();
+        env.forage_radius_m = base.safe_forage_radius_m_min - 1.0;
+        breaches.push(env);
+        for env in breaches {
+            let (band, score) = env.evaluate_band_weighted();
+            assert!(score < 0.33, "{}", score);
+            assert_eq!(band, EcoBand::Warning);
+        }
+    }
+
+    #[test]
+    fn test_toxin_species_each_checked_against_own_limit() {
+        let mut env = sample_envelope();
+        env.toxins = vec![("neonicotinoid".into(), 4.0), ("fungicide".into(), 30.0)];
+        env.safe_toxin_ppb_max_by_species.insert("neonicotinoid".into(), 5.0);
+        env.safe_toxin_ppb_max_by_species.insert("fungicide".into(), 40.0);
+        assert_eq!(env.evaluate_band(), EcoBand::Safe);
+
+        // Neonicotinoid over its own 5 ppb limit, though far below the aggregate 50 ppb.
+        env.toxins[0].1 = 6.0;
+        assert_eq!(env.evaluate_band(), EcoBand::Warning);
+
+        // A species with no configured limit falls back to the aggregate maximum.
+        env.toxins = vec![("pyrethroid".into(), 45.0)];
+        assert_eq!(env.safe_toxin_max_for("pyrethroid"), 50.0);
+        assert_eq!(env.evaluate_band(), EcoBand::Safe);
+    }
+
+    fn push_event(ledger: &mut HiveInnerLedger, env: &HiveEnvelope, hours: i64) {
+        let mut adj = sample_adjustment();
+        adj.hive_id = env.hive_id.clone();
+        adj.timestamp = DateTime::<Utc>::UNIX_EPOCH + chrono::Duration::hours(hours);
+        ledger.record(HiveLedgerEvent {
+            adjustment: adj,
+            pre_envelope: env.clone(),
+            post_envelope: env.clone(),
+        });
+    }
+
+    #[test]
+    fn test_baseline_deviation_flags_toxin_spike() {
+        let mut ledger = HiveInnerLedger::new();
+        for (hour, toxin) in [18.0, 20.0, 22.0, 20.0].into_iter().enumerate() {
+            let mut env = sample_envelope();
+            env.ambient_toxin_ppb = toxin;
+            env.hive_temperature_c = 34.0 + hour as f32 * 0.1;
+            push_event(&mut ledger, &env, hour as i64);
+        }
+
+        let mut current = sample_envelope();
+        current.ambient_toxin_ppb = 30.0;
+        current.hive_temperature_c = 34.15;
+        let dev = baseline_deviation(&ledger, "hive-alpha", &current);
+
+        // Toxin history: mean 20, std sqrt(2); 30 ppb is about 7 sigma out.
+        assert!((dev["ambient_toxin_ppb"] - 10.0 / 2f32.sqrt()).abs() < 1e-3);
+        assert!(dev["hive_temperature_c"].abs() < 1.0);
+        assert_eq!(dev["forage_radius_m"], 0.0);
+        assert!(baseline_deviation(&ledger, "hive-unknown", &current).is_empty());
+    }
+
+    #[test]
+    fn test_summarize_excludes_events_before_window() {
+        let mut ledger = HiveInnerLedger::new();
+        for (hour, temp) in [(0, 30.0), (1, 33.0), (2, 35.0)] {
+            let mut env = sample_envelope();
+            env.hive_temperature_c = temp;
+            push_event(&mut ledger, &env, hour);
+        }
+
+        let since = DateTime::<Utc>::UNIX_EPOCH + chrono::Duration::hours(1);
+        let summary = ledger.summarize(since);
+        assert_eq!(summary.event_count, 2);
+        assert_eq!(summary.net_delta_eco_impact_score_corridor, 10.0);
+        assert_eq!(summary.net_delta_forage_radius_m, 400.0);
+        assert_eq!(summary.min_projected_temp_c, Some(33.0));
+        assert_eq!(summary.max_projected_temp_c, Some(35.0));
+
+        let future = DateTime::<Utc>::UNIX_EPOCH + chrono::Duration::hours(10);
+        assert_eq!(ledger.summarize(future), LedgerSummary::default());
+    }
+
+    #[test]
+    fn test_all_zero_adjustment_is_no_effect() {
+        let mut adj = sample_adjustment();
+        adj.delta_pesticide_exposure_ppb = 0.0;
+        adj.delta_forage_radius_m = 0.0;
+        adj.delta_forage_diversity_index = 0.0;
+        adj.delta_eco_impact_score_corridor = 1.0e-8;
+
+        let mut ledger = HiveInnerLedger::new();
+        let err = ledger.apply_adjustment(sample_envelope(), adj).unwrap_err();
+        assert_eq!(err, HiveGuardError::NoEffect);
+        assert!(ledger.events().is_empty());
+    }
+
+    #[test]
+    fn test_corridor_edit_rolls_back_on_broken_ordering() {
+        use hive::{BandEdit, BandField, CorridorError};
+        let edit = |var_id: &str, field, value| BandEdit { var_id: var_id.into(), field, value };
+
+        let mut corridors = sample_corridors();
+        let err = corridors
+            .edit(vec![
+                edit("toxin_index_air", BandField::Hard, 70.0),
+                // Safe above gold (36.0) breaks ordering.
+                edit("hive_temp_c", BandField::Safe, 36.5),
+            ])
+            .unwrap_err();
+        assert_eq!(err, CorridorError::Malformed("hive_temp_c"));
+        assert_eq!(corridors.toxin_air_bands.hard, 60.0);
+        assert_eq!(corridors.temp_bands.safe, 35.0);
+
+        let diffs = corridors
+            .edit(vec![edit("toxin_index_air", BandField::Hard, 70.0)])
+            .unwrap();
+        assert_eq!(diffs[0].old, 60.0);
+        assert_eq!(diffs[0].new, 70.0);
+        assert_eq!(corridors.toxin_air_bands.hard, 70.0);
+        assert!(matches!(
+            corridors.edit(vec![edit("nope", BandField::Gold, 1.0)]),
+            Err(CorridorError::UnknownCorridor(_))
+        ));
+    }
+
+    #[test]
+    fn test_shade_cooling_scales_with_hive_coefficient() {
+        let mut shade = sample_adjustment();
+        shade.delta_shade_fraction = 0.4;
+        let temperate = sample_envelope();
+        let desert = sample_envelope().with_shade_cooling_coeff_c(2.0);
+
+        assert!((projected_temp_delta(&temperate, &shade) + 2.0).abs() < 1e-6);
+        assert!((projected_temp_delta(&desert, &shade) + 0.8).abs() < 1e-6);
+
+        // Adding shade never heats, even with a nonsensical negative coefficient.
+        let inverted = sample_envelope().with_shade_cooling_coeff_c(-3.0);
+        assert_eq!(projected_temp_delta(&inverted, &shade), 0.0);
+    }
+
+    #[test]
+    fn test_band_zone_boundaries() {
+        use bands::BandZone;
+        let bands = CorridorBands::new("toxin_index_air", "ppb", 10.0, 20.0, 30.0, 1.0, 3, true);
+
+        assert_eq!(bands.zone(0.0), BandZone::Safe);
+        assert_eq!(bands.zone(10.0), BandZone::Safe);
+        assert_eq!(bands.zone(10.01), BandZone::Gold);
+        assert_eq!(bands.zone(20.0), BandZone::Gold);
+        assert_eq!(bands.zone(20.01), BandZone::Hard);
+        assert_eq!(bands.zone(30.0), BandZone::Hard);
+        assert!(!bands.is_violation(30.0));
+        assert_eq!(bands.zone(30.01), BandZone::Over);
+        assert!(bands.is_violation(30.01));
+
+        assert_eq!(risk::to_risk(10.0, &bands), 0.0);
+        assert_eq!(risk::to_risk(30.0, &bands), 1.0);
+        assert_eq!(risk::to_risk(45.0, &bands), 1.0);
+    }
+
+    #[test]
+    fn test_evaluate_hive_derates_between_gold_and_hard() {
+        let corridors = sample_corridors();
+        let mut env = sample_envelope().to_core_envelope();
+        // Toxin air bands are 25/40/60.
+        env.toxin_index_air = 39.0;
+        let res = hive::evaluate_hive(&env, &corridors);
+        assert!(!res.derate && !res.stop);
+        env.toxin_index_air = 45.0;
+        let res = hive::evaluate_hive(&env, &corridors);
+        assert!(res.derate && !res.stop);
+        // Exactly at hard is the edge of the Hard zone, not a violation: full derate.
+        env.toxin_index_air = 60.0;
+        let res = hive::evaluate_hive(&env, &corridors);
+        assert!(res.derate && !res.stop);
+        assert_eq!(res.derate_fraction, 1.0);
+        env.toxin_index_air = 60.01;
+        assert!(hive::evaluate_hive(&env, &corridors).stop);
+    }
+
+    #[test]
+    fn test_evaluate_hive_stops_exactly_when_is_violation() {
+        let mut corridors = sample_corridors();
+        corridors.humidity_bands =
+            CorridorBands::new("hive_humidity_pct", "%", 70.0, 80.0, 95.0, 0.5, 2, true)
+                .with_low_bands(50.0, 30.0);
+        let bands = corridors.humidity_bands.clone();
+        let mut env = sample_envelope().to_core_envelope();
+        env.hive_temp_c = 35.0;
+        for humidity in [29.99, 30.0, 30.01, 60.0, 94.99, 95.0, 95.01] {
+            env.hive_humidity_pct = humidity;
+            let res = hive::evaluate_hive(&env, &corridors);
+            assert_eq!(res.stop, bands.is_violation(humidity), "{}", humidity);
+        }
+        assert!(!bands.is_violation(30.0) && bands.is_violation(29.99));
+    }
+
+    #[test]
+    fn test_evaluate_hive_derates_in_low_hard_zone() {
+        use bands::BandZone;
+        let mut corridors = sample_corridors();
+        corridors.humidity_bands =
+            CorridorBands::new("hive_humidity_pct", "%", 70.0, 80.0, 95.0, 0.5, 2, true)
+                .with_low_bands(50.0, 30.0);
+        let bands = corridors.humidity_bands.clone();
+        // Low gold point is midway: 40%.
+        assert_eq!(bands.zone_two_sided(50.0), BandZone::Safe);
+        assert_eq!(bands.zone_two_sided(40.0), BandZone::Gold);
+        assert_eq!(bands.zone_two_sided(39.9), BandZone::Hard);
+        assert_eq!(bands.zone_two_sided(30.0), BandZone::Hard);
+        assert_eq!(bands.zone_two_sided(29.9), BandZone::Over);
+        assert_eq!(bands.zone_two_sided(90.0), bands.zone(90.0));
+
+        let mut env = sample_envelope().to_core_envelope();
+        env.hive_temp_c = 35.0;
+        env.hive_humidity_pct = 45.0;
+        let res = hive::evaluate_hive(&env, &corridors);
+        assert!(!res.derate && !res.stop);
+
+        // r = (50 - 35) / 20 = 0.75: halfway from low gold to low hard, like the high side.
+        env.hive_humidity_pct = 35.0;
+        let res = hive::evaluate_hive(&env, &corridors);
+        assert!(res.derate && !res.stop);
+        assert!((res.derate_fraction - 0.5).abs() < 1e-12);
+        env.hive_humidity_pct = 87.5;
+        let high = hive::evaluate_hive(&env, &corridors);
+        assert!(high.derate && !high.stop);
+        assert!((high.derate_fraction - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_confidence_gate_derates_uncertain_reading() {
+        let corridors = sample_corridors();
+        let gate = hive::ConfidenceGate { z: 1.0 };
+        let mut env = sample_envelope().to_core_envelope();
+        // r = 0.467 is below gold, but toxin sigma 0.10 puts the upper bound at 0.567.
+        env.toxin_index_air = 39.0;
+        assert!(!hive::evaluate_hive(&env, &corridors).derate);
+        let res = hive::evaluate_hive_gated(&env, &corridors, &gate);
+        assert!(res.derate && !res.stop);
+        assert!((res.derate_fraction - 0.1333).abs() < 1e-3);
+
+        env.toxin_index_air = 30.0;
+        assert!(!hive::evaluate_hive_gated(&env, &corridors, &gate).derate);
+    }
+
+    #[test]
+    fn test_corridor_bands_try_new_rejects_invalid() {
+        use bands::BandError;
+        assert!(CorridorBands::try_new("toxin_index_air", "ppb", 10.0, 20.0, 30.0, 1.0, 3, true).is_ok());
+
+        let inverted = CorridorBands::try_new("toxin_index_air", "ppb", 30.0, 20.0, 10.0, 1.0, 3, true);
+        assert!(matches!(
+            inverted,
+            Err(BandError::Unordered { safe, hard, .. }) if safe == 30.0 && hard == 10.0
+        ));
+
+        let negative = CorridorBands::try_new("toxin_index_air", "ppb", 10.0, 20.0, 30.0, -0.5, 3, true);
+        assert_eq!(
+            negative.unwrap_err(),
+            BandError::NegativeWeight { var_id: "toxin_index_air", weight: -0.5 }
+        );
+
+        let mut corridors = sample_corridors();
+        assert!(hive::corridor_present(&corridors));
+        corridors.humidity_bands.weight = -1.0;
+        assert!(!hive::corridor_present(&corridors));
+    }
+
+    #[test]
+    fn test_corridor_report_names_malformed_mandatory_band() {
+        use hive::CorridorDefect;
+        let mut corridors = sample_corridors();
+        assert!(hive::corridor_report(&corridors).is_empty());
+
+        corridors.toxin_wax_bands.gold = 50.0;
+        // Optional bands are not reported even when malformed.
+        corridors.forager_load_bands.hard = 0.0;
+        corridors.forager_load_bands.mandatory = false;
+        assert_eq!(
+            hive::corridor_report(&corridors),
+            vec![("toxin_index_wax", CorridorDefect::GoldAboveHard)]
+        );
+        assert!(!hive::corridor_present(&corridors));
+
+        corridors.toxin_wax_bands.gold = 20.0;
+        corridors.temp_bands.safe = 36.5;
+        corridors.humidity_bands.hard = -1.0;
+        assert_eq!(
+            hive::corridor_report(&corridors),
+            vec![
+                ("hive_temp_c", CorridorDefect::SafeAboveGold),
+                ("hive_humidity_pct", CorridorDefect::NonPositiveHard),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_corridor_report_agrees_with_band_validate() {
+        use hive::CorridorDefect;
+        let band = |safe, gold, hard| {
+            CorridorBands::new("hive_temp_c", "C", safe, gold, hard, 1.0, 0, true)
+        };
+        let cases = [
+            (band(35.0, 36.0, 38.0), None),
+            (band(35.0, 36.0, 0.0), Some(CorridorDefect::NonPositiveHard)),
+            (band(35.0, 36.0, f64::NAN), Some(CorridorDefect::NonPositiveHard)),
+            (band(35.0, 39.0, 38.0), Some(CorridorDefect::GoldAboveHard)),
+            (band(37.0, 36.0, 38.0), Some(CorridorDefect::SafeAboveGold)),
+            (band(-1.0, 36.0, 38.0), Some(CorridorDefect::NegativeSafe)),
+            (
+                CorridorBands { weight: -0.5, ..band(35.0, 36.0, 38.0) },
+                Some(CorridorDefect::NegativeWeight),
+            ),
+            (
+                band(35.0, 36.0, 38.0).with_low_bands(34.0, 36.0),
+                Some(CorridorDefect::LowBandsUnordered),
+            ),
+        ];
+        for (bands, expected) in cases {
+            let mut corridors = sample_corridors();
+            corridors.temp_bands = bands.clone();
+            let reported = hive::corridor_report(&corridors).first().map(|(_, d)| *d);
+            assert_eq!(reported, expected, "{:?}", bands);
+            assert_eq!(reported.is_none(), bands.validate().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_evaluate_hive_skips_disabled_corridor() {
+        let mut corridors = sample_corridors();
+        let mut env = sample_envelope().to_core_envelope();
+        env.hive_temp_c = 35.0;
+        env.brood_temp_c = 35.0;
+        env.toxin_index_air = 20.0;
+        env.forage_radius_km = 2.0;
+        env.forager_load_pct = 0.5;
+        env.hive_humidity_pct = 60.0;
+        let baseline = hive::evaluate_hive(&env, &corridors);
+        assert_eq!(baseline.coords.len(), 7);
+
+        corridors.humidity_bands = CorridorBands::new("hive_humidity_pct", "%", 0.0, 0.0, 0.0, 0.5, 2, false);
+        let res = hive::evaluate_hive(&env, &corridors);
+        assert!(!res.stop);
+        assert_eq!(res.coords.len(), 6);
+        assert!(res.coords.iter().all(|c| c.var_id != "hive_humidity_pct"));
+        assert_eq!(res.vt, baseline.vt);
+
+        // A zero-width range is a step at the hard limit; a mandatory step band stops.
+        assert_eq!(risk::to_risk(0.0, &corridors.humidity_bands), 0.0);
+        assert_eq!(risk::to_risk(10.0, &corridors.humidity_bands), 1.0);
+        corridors.temp_bands = CorridorBands::new("hive_temp_c", "C", 36.0, 36.0, 36.0, 1.0, 0, true);
+        env.hive_temp_c = 36.5;
+        assert!(hive::evaluate_hive(&env, &corridors).stop);
+    }
+
+    #[test]
+    fn test_to_risk_step_band_breach_is_full_risk() {
+        let step = CorridorBands::new("hive_temp_c", "C", 36.0, 36.0, 36.0, 1.0, 0, true);
+        assert_eq!(risk::to_risk(35.0, &step), 0.0);
+        assert_eq!(risk::to_risk(36.0, &step), 0.0);
+        assert_eq!(risk::to_risk(36.5, &step), 1.0);
+        assert_eq!(risk::to_risk_lipschitz(&step), f64::INFINITY);
+
+        let mut corridors = sample_corridors();
+        corridors.temp_bands = step;
+        let mut env = sample_envelope().to_core_envelope();
+        env.hive_temp_c = 36.5;
+        let res = hive::evaluate_hive(&env, &corridors);
+        let temp = res.coords.iter().find(|c| c.var_id == "hive_temp_c").unwrap();
+        assert_eq!(temp.value, 1.0);
+    }
+
+    #[test]
+    fn test_derate_factor_halfway_between_gold_and_hard() {
+        let corridors = sample_corridors();
+        let mut env = sample_envelope().to_core_envelope();
+        env.hive_temp_c = 35.0;
+        env.hive_humidity_pct = 60.0;
+        env.forage_radius_km = 2.0;
+        env.toxin_index_air = 30.0;
+        assert_eq!(risk::derate_factor(&hive::evaluate_hive(&env, &corridors)), 1.0);
+
+        // Toxin air bands gold 40, hard 60.
+        env.toxin_index_air = 50.0;
+        let res = hive::evaluate_hive(&env, &corridors);
+        assert!((risk::derate_factor(&res) - 0.5).abs() < 1e-12);
+
+        env.toxin_index_air = 60.0;
+        assert_eq!(risk::derate_factor(&hive::evaluate_hive(&env, &corridors)), 0.0);
+    }
+
+    #[test]
+    fn test_policy_check_names_each_rejection() {
+        use hive::PolicyRejection;
+        let corridors = sample_corridors();
+        let mut before = sample_envelope().to_core_envelope();
+        before.hive_temp_c = 35.0;
+        before.forage_radius_km = 3.0;
+        assert_eq!(hive::policy_check(&before, &before.clone(), &corridors), Ok(()));
+
+        let check = |edit: fn(&mut hive::HiveEnvelope)| {
+            let mut after = before.clone();
+            edit(&mut after);
+            hive::policy_check(&before, &after, &corridors).unwrap_err()
+        };
+        assert_eq!(
+            check(|e| e.toxin_index_air += 5.0),
+            PolicyRejection::ToxinAirIncrease { before: 20.0, after: 25.0 }
+        );
+        assert_eq!(
+            check(|e| e.toxin_index_wax += 1.0),
+            PolicyRejection::ToxinWaxIncrease { before: 0.0, after: 1.0 }
+        );
+        assert_eq!(
+            check(|e| e.hive_temp_c = 36.5),
+            PolicyRejection::TemperatureCrossing { before: 35.0, after: 36.5 }
+        );
+        assert!(matches!(
+            check(|e| e.forage_radius_km = 5.0),
+            PolicyRejection::ForageShrink { before, after } if before == 0.0 && after > 0.5
+        ));
+        let mut after = before.clone();
+        after.toxin_index_air += 1.0;
+        assert!(!hive::policy_allows_adjustment(&before, &after, &corridors));
+    }
+
+    #[test]
+    fn test_two_sided_humidity_penalizes_low_readings() {
+        let one_sided = CorridorBands::new("hive_humidity_pct", "%", 70.0, 80.0, 95.0, 0.5, 2, true);
+        let two_sided = one_sided.clone().with_low_bands(50.0, 30.0);
+        assert!(two_sided.validate().is_ok());
+
+        assert_eq!(risk::to_risk_two_sided(40.0, &one_sided), 0.0);
+        assert!((risk::to_risk_two_sided(40.0, &two_sided) - 0.5).abs() < 1e-12);
+        assert_eq!(risk::to_risk_two_sided(20.0, &two_sided), 1.0);
+        assert_eq!(risk::to_risk_two_sided(60.0, &two_sided), 0.0);
+        // The upper side is unchanged.
+        assert_eq!(risk::to_risk_two_sided(87.5, &two_sided), risk::to_risk(87.5, &two_sided));
+
+        let mut corridors = sample_corridors();
+        corridors.humidity_bands = two_sided;
+        let mut env = sample_envelope().to_core_envelope();
+        env.hive_temp_c = 35.0;
+        env.hive_humidity_pct = 40.0;
+        let humidity = |res: &risk::Residual| {
+            res.coords.iter().find(|c| c.var_id == "hive_humidity_pct").unwrap().value
+        };
+        assert!(humidity(&hive::evaluate_hive(&env, &corridors)) > 0.0);
+        env.hive_humidity_pct = 25.0;
+        assert!(hive::evaluate_hive(&env, &corridors).stop);
+
+        let inverted = CorridorBands::new("hive_humidity_pct", "%", 70.0, 80.0, 95.0, 0.5, 2, true)
+            .with_low_bands(30.0, 50.0);
+        assert!(matches!(inverted.validate(), Err(bands::BandError::UnorderedLow { .. })));
+    }
+
+    #[test]
+    fn test_corridors_builder_leaves_unset_bands_optional() {
+        let corridors = HiveCorridors::builder()
+            .with_temp_bands(CorridorBands::new("hive_temp_c", "C", 35.0, 36.0, 38.0, 1.0, 0, true))
+            .with_toxin_air_bands(CorridorBands::new("toxin_index_air", "ppb", 25.0, 40.0, 60.0, 2.0, 3, true))
+            .build();
+        assert!(corridors.temp_bands.mandatory && corridors.toxin_air_bands.mandatory);
+        for unset in [
+            &corridors.brood_temp_bands,
+            &corridors.humidity_bands,
+            &corridors.toxin_wax_bands,
+            &corridors.forage_radius_bands,
+            &corridors.forager_load_bands,
+        ] {
+            assert!(!unset.mandatory);
+            assert_eq!((unset.hard, unset.weight), (0.0, 0.0));
+        }
+        assert!(hive::corridor_present(&corridors));
+
+        let mut env = sample_envelope().to_core_envelope();
+        env.toxin_index_air = 50.0;
+        let res = hive::evaluate_hive(&env, &corridors);
+        assert_eq!(res.coords.len(), 2);
+        assert!(res.derate && !res.stop);
+        assert!(!res.no_coverage);
+    }
+
+    #[test]
+    fn test_evaluate_hive_flags_no_coverage() {
+        let env = sample_envelope().to_core_envelope();
+        let res = hive::evaluate_hive(&env, &HiveCorridors::builder().build());
+        assert!(res.coords.is_empty());
+        assert!(res.no_coverage);
+        assert!(res.stop);
+        assert_eq!(res.derate_fraction, 1.0);
+
+        // One measured corridor is coverage, even when its reading is perfectly safe.
+        let corridors = HiveCorridors::builder()
+            .with_temp_bands(CorridorBands::new("hive_temp_c", "C", 35.0, 36.0, 38.0, 1.0, 0, true))
+            .build();
+        let mut env = env;
+        env.hive_temp_c = 34.0;
+        let res = hive::evaluate_hive(&env, &corridors);
+        assert!(!res.no_coverage && !res.stop);
+        assert_eq!(res.vt, 0.0);
+    }
+
+    #[test]
+    fn test_ker_admits_at_boundaries() {
+        let env = sample_envelope().to_core_envelope();
+        let mut coords = vec![
+            coord("hive_temp_c", 0.2, 1.0),
+            coord("toxin_index_air", 0.6, 1.0),
+            coord("forage_radius_km", 0.4, 2.0),
+            coord("brood_temp_c", 0.0, 0.0),
+        ];
+        // Provenance, not the sigma value, decides: a measured sigma may equal
+        // the placeholder.
+        coords[0].sigma_measured = true;
+        coords[1].sigma = 0.3;
+        coords[1].sigma_measured = true;
+        let ker = hive::ker_from_residual(&env, &residual_of(coords));
+        assert_eq!(ker.knowledge_factor, 0.5);
+        assert_eq!(ker.eco_impact, 1.0);
+        // (0.2 + 0.6 + 0.8) / 4
+        assert!((ker.risk_of_harm - 0.4).abs() < 1e-12);
+
+        let ker = hive::HiveKER { risk_of_harm: 0.4, ..ker };
+        assert!(hive::ker_admits(&ker, 0.5, 0.4));
+        assert!(!hive::ker_admits(&ker, 0.51, 0.4));
+        assert!(!hive::ker_admits(&ker, 0.5, 0.39));
+
+        let empty = hive::ker_from_residual(&env, &residual_of(Vec::new()));
+        assert_eq!((empty.knowledge_factor, empty.risk_of_harm), (0.0, 1.0));
+        assert!(!hive::ker_admits(&empty, 0.0, 0.9));
+
+        // `evaluate_hive` only assigns placeholder sigmas.
+        let evaluated = hive::evaluate_hive(&env, &sample_corridors());
+        assert_eq!(hive::ker_from_residual(&env, &evaluated).knowledge_factor, 0.0);
+    }
+
+    #[test]
+    fn test_to_risk_gold_is_half_and_narrower_segment_is_steeper() {
+        let bands = CorridorBands::new("toxin_index_air", "ppb", 25.0, 40.0, 60.0, 2.0, 3, true);
+        assert_eq!(risk::to_risk(40.0, &bands), 0.5);
+        assert!((risk::to_risk(32.5, &bands) - 0.25).abs() < 1e-12);
+        assert!((risk::to_risk(50.0, &bands) - 0.75).abs() < 1e-12);
+        assert_eq!(risk::to_risk(60.0, &bands), 1.0);
+
+        // Lower segment is 15 wide, upper 20: the lower one is steeper here.
+        assert!((risk::to_risk_lipschitz(&bands) - 0.5 / 15.0).abs() < 1e-12);
+        // Lower segment 2 wide, upper 1: here the upper one is steeper.
+        let tight = CorridorBands::new("hive_temp_c", "C", 35.0, 37.0, 38.0, 1.0, 0, true);
+        assert_eq!(risk::to_risk(37.0, &tight), 0.5);
+        let lower = risk::to_risk(36.0, &tight) - risk::to_risk(35.0, &tight);
+        let upper = risk::to_risk(38.0, &tight) - risk::to_risk(37.0, &tight);
+        assert!(upper > lower);
+        assert!((risk::to_risk_lipschitz(&tight) - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_conservative_residual_accounts_for_sigma() {
+        let mut coords = vec![coord("toxin_index_air", 0.4, 2.0), coord("hive_temp_c", 0.98, 1.0)];
+        let nominal = risk::compute_residual(&coords);
+        let conservative = risk::compute_residual_conservative(&coords);
+        // sigma 0.05 each: 2 * 0.45 + min(1.03, 1) = 1.9 vs nominal 1.78.
+        assert!(conservative > nominal);
+        assert!((conservative - 1.9).abs() < 1e-12);
+
+        for c in &mut coords {
+            c.sigma = 0.0;
+        }
+        assert_eq!(risk::compute_residual_conservative(&coords), risk::compute_residual(&coords));
+    }
+
+    #[test]
+    fn test_residual_contributions_rank_dominant_variable_first() {
+        let coords = vec![
+            coord("hive_temp_c", 0.4, 1.0),
+            coord("toxin_index_air", 0.9, 2.0),
+            coord("forage_radius_m", 0.2, 1.0),
+        ];
+        let terms = risk::residual_contributions(&coords);
+        assert_eq!(terms.len(), 3);
+        assert_eq!(terms[0].0, "toxin_index_air");
+        assert!((terms[0].1 - 1.8).abs() < 1e-12);
+        assert_eq!(terms[2].0, "forage_radius_m");
+        let total: f64 = terms.iter().map(|(_, t)| t).sum();
+        assert!((total - risk::compute_residual(&coords)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_stop_hysteresis_latches_through_dead_band() {
+        let h = hive::StopHysteresis { enter: 0.8, exit: 0.5 };
+        let mut state = hive::StopState::default();
+        let at = |v: f64| residual_of(vec![coord("toxin_index_air", v, 1.0)]);
+
+        let trace = [0.6, 0.85, 0.7, 0.6, 0.45, 0.55];
+        let mut prev = at(0.6);
+        let mut stops = Vec::new();
+        for v in trace {
+            let next = at(v);
+            stops.push(hive::safe_step_hysteretic(&mut state, &prev, &next, &h, 0.0).stop);
+            prev = next;
+        }
+        // 0.6 -> 0.6 is flat; 0.85 enters; 0.7 and 0.6 sit in the dead band;
+        // 0.45 releases; rising back to 0.55 trips the plain Lyapunov rule.
+        assert_eq!(stops, vec![false, true, true, true, false, true]);
+
+        // A noise margin covering the 0.1 rise lets the released hive keep running.
+        let mut state = hive::StopState::default();
+        let step = |state: &mut hive::StopState, from: f64, to: f64, margin: f64| {
+            hive::safe_step_hysteretic(state, &at(from), &at(to), &h, margin).stop
+        };
+        assert!(!step(&mut state, 0.45, 0.55, 0.15));
+        assert!(step(&mut state, 0.45, 0.55, 0.05));
+
+        // Without the latch the same descent clears immediately.
+        assert!(!hive::safe_step(&at(0.85), &at(0.7), 0.0).stop);
+    }
+
+    #[test]
+    fn test_regional_risk_aggregators() {
+        use hive::RegionalAggregator;
+        let residuals: Vec<risk::Residual> = [0.5, 0.1, 0.4, 0.2, 0.3]
+            .iter()
+            .map(|&v| residual_of(vec![coord("toxin_index_air", v, 1.0)]))
+            .collect();
+
+        let agg = |a| hive::regional_risk(&residuals, a);
+        assert_eq!(agg(RegionalAggregator::Worst), 0.5);
+        assert!((agg(RegionalAggregator::Mean) - 0.3).abs() < 1e-12);
+        // rank 0.9 * 4 = 3.6 between 0.4 and 0.5.
+        assert!((agg(RegionalAggregator::Percentile(90.0)) - 0.46).abs() < 1e-12);
+        assert_eq!(agg(RegionalAggregator::Percentile(0.0)), 0.1);
+        assert_eq!(agg(RegionalAggregator::Percentile(100.0)), 0.5);
+        assert_eq!(hive::regional_risk(&[], RegionalAggregator::Worst), 0.0);
+    }
+
+    #[test]
+    fn test_safe_step_lookahead_stops_at_first_breach() {
+        let corridors = sample_corridors();
+        let mut env = sample_envelope().to_core_envelope();
+        // Toxin air hard limit is 60: the 60.0 reading sits on it, 61.0 is past it.
+        let steps: Vec<risk::Residual> = [30.0, 60.0, 61.0, 50.0]
+            .iter()
+            .map(|&toxin| {
+                env.toxin_index_air = toxin;
+                hive::evaluate_hive(&env, &corridors)
+            })
+            .collect();
+        let decision = hive::safe_step_lookahead(&steps);
+        assert!(decision.stop);
+        assert_eq!(decision.breach_index, Some(2));
+
+        let calm = hive::safe_step_lookahead(&steps[..2]);
+        assert_eq!(calm, hive::StepDecision { stop: false, breach_index: None });
+    }
+
+    #[test]
+    fn test_derate_fraction_scales_with_overshoot_past_gold() {
+        let corridors = sample_corridors();
+        let mut env = sample_envelope().to_core_envelope();
+        // Toxin air bands are 25/40/60: gold at 40, hard at 60.
+        let fraction_at = |env: &mut hive::HiveEnvelope, toxin: f64| {
+            env.toxin_index_air = toxin;
+            hive::evaluate_hive(env, &corridors).derate_fraction
+        };
+
+        assert_eq!(fraction_at(&mut env, 30.0), 0.0);
+        assert_eq!(fraction_at(&mut env, 40.0), 0.0);
+        assert!((fraction_at(&mut env, 45.0) - 0.25).abs() < 1e-12);
+        assert!((fraction_at(&mut env, 55.0) - 0.75).abs() < 1e-12);
+        assert_eq!(fraction_at(&mut env, 60.0), 1.0);
+
+        // Lyapunov stop in safe_step forces a full throttle.
+        env.toxin_index_air = 45.0;
+        let prev = hive::evaluate_hive(&env, &corridors);
+        env.toxin_index_air = 50.0;
+        let next = hive::evaluate_hive(&env, &corridors);
+        assert!((next.derate_fraction - 0.5).abs() < 1e-12);
+        assert_eq!(hive::safe_step(&prev, &next, 0.0).derate_fraction, 1.0);
+    }
+
+    #[test]
+    fn test_safe_step_margin_absorbs_small_increases() {
+        let prev = residual_of(vec![coord("toxin_index_air", 0.4, 1.0)]);
+        let nudged = residual_of(vec![coord("toxin_index_air", 0.41, 1.0)]);
+        let jumped = residual_of(vec![coord("toxin_index_air", 0.6, 1.0)]);
+
+        let quiet = hive::safe_step(&prev, &nudged, 0.05);
+        assert!(!quiet.stop);
+        assert!(!quiet.derate);
+        assert!(hive::safe_step(&prev, &nudged, 0.0).stop);
+
+        let tripped = hive::safe_step(&prev, &jumped, 0.05);
+        assert!(tripped.stop);
+        assert!(tripped.derate);
+
+        // A reading past hard stops regardless of the margin, even when V_t
+        // does not rise because both readings clamp to r = 1.
+        let corridors = sample_corridors();
+        let mut env = sample_envelope().to_core_envelope();
+        env.toxin_index_air = 62.0;
+        let breach = hive::evaluate_hive(&env, &corridors);
+        env.toxin_index_air = 61.0;
+        let over = hive::evaluate_hive(&env, &corridors);
+        assert!((over.vt - breach.vt).abs() < 1e-12);
+        assert!(hive::safe_step(&breach, &over, 10.0).stop);
+    }
+
+    #[test]
+    fn test_need_centroid_pulls_toward_risky_cluster() {
+        let at = |id: &str, eco: f32| {
+            let mut h = sample_envelope();
+            h.hive_id = id.into();
+            h.eco_impact_score_corridor = eco;
+            h
+        };
+        let hives = vec![at("healthy", 90.0), at("risky-a", 20.0), at("risky-b", 20.0)];
+        let positions: HashMap<String, (f64, f64)> = [
+            ("healthy".to_string(), (0.0, 0.0)),
+            ("risky-a".to_string(), (100.0, 0.0)),
+            ("risky-b".to_string(), (100.0, 20.0)),
+        ]
+        .into_iter()
+        .collect();
+
+        let (x, y) = need_centroid(&hives, &positions).unwrap();
+        // Weights 0.1, 0.8, 0.8: x = 160 / 1.7.
+        assert!((x - 160.0 / 1.7).abs() < 1e-4);
+        assert!((y - 16.0 / 1.7).abs() < 1e-4);
+        assert!(x > 50.0);
+        assert_eq!(need_centroid(&[], &positions), None);
+
+        // A soft-overloaded hive's penalty counts as need: 90 less 100 * 0.7 leaves
+        // an effective 20, so the healthy hive now weighs as much as a risky one.
+        let mut hives = hives;
+        hives[0].safe_forager_load_max = Some(0.0);
+        hives[0].forager_load_policy = ForagerLoadPolicy::SoftPenalty;
+        assert!((hives[0].effective_eco_impact_score() - 20.0).abs() < 1e-4);
+        let (x, _) = need_centroid(&hives, &positions).unwrap();
+        assert!((x - 160.0 / 2.4).abs() < 1e-3, "{}", x);
+    }
+
+    #[test]
+    fn test_forager_load_policies_agree_only_within_limits() {
+        let with_policy = |load: f32, policy| HiveEnvelope {
+            forager_load: load,
+            safe_forager_load_max: Some(0.8),
+            forager_load_policy: policy,
+            ..sample_envelope()
+        };
+
+        let hard = with_policy(0.7, ForagerLoadPolicy::HardBand);
+        let soft = with_policy(0.7, ForagerLoadPolicy::SoftPenalty);
+        assert_eq!(hard.evaluate_band(), EcoBand::Safe);
+        assert_eq!(soft.evaluate_band(), EcoBand::Safe);
+        assert_eq!(soft.effective_eco_impact_score(), 75.0);
+
+        let hard = with_policy(0.95, ForagerLoadPolicy::HardBand);
+        let soft = with_policy(0.95, ForagerLoadPolicy::SoftPenalty);
+        assert!(hard.forager_overloaded() && soft.forager_overloaded());
+        assert_eq!(hard.evaluate_band(), EcoBand::Warning);
+        assert_eq!(soft.evaluate_band(), EcoBand::Safe);
+        assert_eq!(hard.effective_eco_impact_score(), 75.0);
+        // 0.15 excess * 100 points.
+        assert!((soft.effective_eco_impact_score() - 60.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_validate_envelopes_json_isolates_per_file_errors() {
+        let good = serde_json::to_string(&sample_envelope()).unwrap();
+        // serde_json writes NaN as null, which a f32 field rejects; a literal NaN
+        // token is not JSON at all. Both must fail only their own file.
+        let nan = good.replacen("\"nectar_kg\":12.0", "\"nectar_kg\":NaN", 1);
+        assert_ne!(nan, good);
+        let mut inverted = sample_envelope();
+        inverted.safe_temperature_c_min = 40.0;
+        let inverted = serde_json::to_string(&inverted).unwrap();
+
+        let results = validate_envelopes_json(&[
+            ("a.json".to_string(), good),
+            ("b.json".to_string(), nan),
+            ("c.json".to_string(), inverted),
+        ]);
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0], ("a.json".to_string(), Ok(())));
+        assert_eq!(results[1].0, "b.json");
+        assert!(results[1].1.as_ref().unwrap_err()[0].starts_with("invalid JSON"));
+        assert_eq!(results[2].1.as_ref().unwrap_err().len(), 1);
+
+        let mut env = sample_envelope();
+        env.hive_temperature_c = f32::NAN;
+        env.toxins.push(("imidacloprid".into(), f32::INFINITY));
+        let problems = env.sanitize();
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].starts_with("hive_temperature_c"));
+        assert!(problems[1].starts_with("toxins[imidacloprid]"));
+    }
+
+    #[test]
+    fn test_baseline_drift_decays_toxin_and_degrades_habitat() {
+        let mut env = sample_envelope();
+        env.ambient_toxin_ppb = 80.0;
+        env.eco_band = env.evaluate_band();
+        assert_eq!(env.eco_band, EcoBand::Warning);
+        let drift = DriftModel {
+            toxin_half_life_steps: 2.0,
+            temp_trend_c_per_step: 0.1,
+            diversity_loss_per_step: 0.01,
+            forage_radius_loss_m_per_step: 10.0,
+        };
+
+        let path = baseline_drift(env.clone(), &drift, 4);
+        assert_eq!(path.len(), 4);
+        // Two half-lives: 80 -> 20 ppb, back under the 50 ppb limit.
+        let last = &path[3];
+        assert!((last.ambient_toxin_ppb - 20.0).abs() < 1e-3);
+        assert_eq!(last.eco_band, EcoBand::Safe);
+        assert!((last.forage_diversity_index - 0.76).abs() < 1e-5);
+        assert_eq!(last.forage_radius_m, 1460.0);
+        assert!((last.hive_temperature_c - 34.4).abs() < 1e-4);
+        assert!(path.windows(2).all(|w| {
+            w[1].ambient_toxin_ppb < w[0].ambient_toxin_ppb
+                && w[1].forage_diversity_index < w[0].forage_diversity_index
+        }));
+        assert!(baseline_drift(env, &drift, 0).is_empty());
+    }
+
+    #[test]
+    fn test_binding_invariant_finds_closest_limit() {
+        // 1050 m against a 1000 m floor: a 5% shrink binds; heat needs 80% unshading.
+        let mut env = sample_envelope();
+        env.forage_radius_m = 1050.0;
+        assert_eq!(binding_invariant(&env), Some(HiveGuardError::ReducesForageRadius));
+
+        env.hive_temperature_c = 35.9;
+        assert_eq!(binding_invariant(&env), Some(HiveGuardError::RaisesHiveTemperature));
+
+        // No shade leverage and a radius that cannot shrink below a zero floor.
+        env.shade_cooling_coeff_c = 0.0;
+        env.safe_forage_radius_m_min = 0.0;
+        assert_eq!(binding_invariant(&env), None);
+    }
+
+    #[test]
+    fn test_scheduling_fairness_rewards_helping_critical_hives() {
+        let mut critical = sample_envelope();
+        critical.hive_id = "hive-critical".into();
+        critical.hive_temperature_c = 30.0;
+        critical.ambient_toxin_ppb = 80.0;
+        critical.forage_diversity_index = 0.3;
+        assert_eq!(critical.evaluate_band(), EcoBand::Critical);
+        let mut warning = sample_envelope();
+        warning.hive_id = "hive-warning".into();
+        warning.ambient_toxin_ppb = 80.0;
+        let safe = sample_envelope();
+
+        // Critical hive helped three times, warning once, safe never.
+        let mut fair = HiveInnerLedger::new();
+        for h in [0, 10, 20] {
+            push_event(&mut fair, &critical, h);
+        }
+        push_event(&mut fair, &warning, 5);
+        let hives = vec![critical.clone(), warning.clone(), safe.clone()];
+        assert!(scheduling_fairness(&fair, &hives) > 0.8);
+
+        // Help went to the safe hive while the critical one waited.
+        let mut unfair = HiveInnerLedger::new();
+        for h in [0, 10, 20] {
+            push_event(&mut unfair, &safe, h);
+        }
+        // need [20, 0, 0] vs help [0, 0, 3].
+        assert!((scheduling_fairness(&unfair, &hives) + 0.5).abs() < 1e-12);
+        assert_eq!(scheduling_fairness(&HiveInnerLedger::new(), &hives), 0.0);
+    }
+
+    #[test]
+    fn test_audit_bundle_matches_ledger_and_hashes_stably() {
+        let mut toxic = sample_envelope();
+        toxic.ambient_toxin_ppb = 55.0;
+        let mut ledger = HiveInnerLedger::new();
+        let post = ledger.apply_adjustment(toxic, sample_adjustment()).unwrap();
+        let mut other = sample_envelope();
+        other.hive_id = "hive-beta".into();
+        let mut adj = sample_adjustment();
+        adj.id = "adj-hive-beta-task-1".into();
+        adj.hive_id = "hive-beta".into();
+        ledger.apply_adjustment(other, adj).unwrap();
+
+        let bundle = audit_bundle(&ledger, "hive-alpha", &post);
+        let expected: Vec<HiveLedgerEvent> =
+            ledger.events_for("hive-alpha").into_iter().cloned().collect();
+        assert_eq!(bundle.events, expected);
+        assert_eq!(bundle.events.len(), 1);
+        assert_eq!(bundle.cumulative_pesticide_reduction_ppb, 10.0);
+        assert_eq!(bundle.band_transitions.len(), 1);
+        assert_eq!(
+            (bundle.band_transitions[0].from, bundle.band_transitions[0].to),
+            (EcoBand::Warning, EcoBand::Safe)
+        );
+
+        assert_eq!(audit_bundle(&ledger, "hive-alpha", &post).content_hash, bundle.content_hash);
+        let json = serde_json::to_string(&bundle).unwrap();
+        let reloaded: AuditBundle = serde_json::from_str(&json).unwrap();
+        assert_eq!(reloaded.content_hash, bundle.content_hash);
+        let mut changed = post.clone();
+        changed.nectar_kg += 1.0;
+        assert_ne!(audit_bundle(&ledger, "hive-alpha", &changed).content_hash, bundle.content_hash);
+    }
+
+    #[test]
+    fn test_band_matrix_corners() {
+        let grid = band_matrix(&sample_envelope(), (33.0, 40.0, 4), (10.0, 90.0, 5));
+        assert_eq!(grid.len(), 4);
+        assert!(grid.iter().all(|row| row.len() == 5));
+        assert_eq!(grid[0][0], EcoBand::Safe);
+        assert_eq!(grid[3][4], EcoBand::Warning);
+
+        // Critical also needs forage breached, which the sweep leaves at the base value.
+        let sparse = HiveEnvelope { forage_diversity_index: 0.3, ..sample_envelope() };
+        let grid = band_matrix(&sparse, (33.0, 40.0, 4), (10.0, 90.0, 5));
+        assert_eq!(grid[0][0], EcoBand::Warning);
+        assert_eq!(grid[3][4], EcoBand::Critical);
+        assert!(band_matrix(&sparse, (33.0, 40.0, 0), (10.0, 90.0, 5)).is_empty());
+    }
+
+    #[test]
+    fn test_survival_estimate_penalizes_critical_history() {
+        let safe = sample_envelope();
+        let mut critical = sample_envelope();
+        critical.hive_id = "hive-beta".into();
+        critical.hive_temperature_c = 30.0;
+        critical.ambient_toxin_ppb = 80.0;
+        critical.forage_diversity_index = 0.3;
+        assert_eq!(critical.evaluate_band(), EcoBand::Critical);
+
+        let mut ledger = HiveInnerLedger::new();
+        for h in [0, 12, 24] {
+            push_event(&mut ledger, &safe, h);
+            push_event(&mut ledger, &critical, h);
+        }
+
+        let last = DateTime::<Utc>::UNIX_EPOCH + chrono::Duration::hours(24);
+        let s_safe = survival_estimate_at(&ledger, "hive-alpha", last);
+        let s_critical = survival_estimate_at(&ledger, "hive-beta", last);
+        assert_eq!(s_safe, 1.0);
+        assert!(s_critical < s_safe);
+        assert!(s_critical > 0.0);
+
+        // The last event's state keeps accruing hazard until `as_of`.
+        let later = last + chrono::Duration::hours(12);
+        assert_eq!(survival_estimate_at(&ledger, "hive-alpha", later), 1.0);
+        assert!(survival_estimate_at(&ledger, "hive-beta", later) < s_critical);
+        // An `as_of` before the last event adds no tail.
+        let early = DateTime::<Utc>::UNIX_EPOCH;
+        assert_eq!(survival_estimate_at(&ledger, "hive-beta", early), s_critical);
+        // The wall-clock form holds the tail until now, long after the events.
+        assert_eq!(survival_estimate(&ledger, "hive-alpha"), 1.0);
+        let s_later = survival_estimate_at(&ledger, "hive-beta", later);
+        assert!(survival_estimate(&ledger, "hive-beta") < s_later);
+    }
+
+    #[test]
+    fn test_survival_estimate_counts_single_critical_event() {
+        let mut critical = sample_envelope();
+        critical.hive_temperature_c = 30.0;
+        critical.ambient_toxin_ppb = 80.0;
+        critical.forage_diversity_index = 0.3;
+        let mut ledger = HiveInnerLedger::new();
+        push_event(&mut ledger, &critical, 0);
+
+        let start = DateTime::<Utc>::UNIX_EPOCH;
+        assert_eq!(survival_estimate_at(&ledger, "hive-alpha", start), 1.0);
+        let day_end = start + chrono::Duration::hours(24);
+        let day = survival_estimate_at(&ledger, "hive-alpha", day_end);
+        let rate = CRITICAL_HAZARD_PER_HOUR + OVERSHOOT_HAZARD_PER_HOUR * overshoot(&critical);
+        let expected = -rate * 24.0;
+        assert!((day - expected.exp()).abs() < 1e-12, "{}", day);
+    }
+
+    fn coord(var_id: &'static str, value: f64, weight: f64) -> risk::RiskCoord {
+        risk::RiskCoord {
+            var_id,
+            value,
+            sigma: 0.05,
+            sigma_measured: false,
+            bands: CorridorBands::new(var_id, "dimensionless", 0.2, 0.5, 1.0, weight, 0, true),
+            over: false,
+        }
+    }
+
+    fn residual_of(coords: Vec<risk::RiskCoord>) -> risk::Residual {
+        risk::Residual {
+            vt: risk::compute_residual(&coords),
+            coords,
+            derate: false,
+            derate_fraction: 0.0,
+            stop: false,
+            no_coverage: false,
+        }
+    }
+
+    #[test]
+    fn test_residual_per_active_corridor_normalizes_by_count() {
+        let two = residual_of(vec![coord("temp", 0.3, 1.0), coord("toxin", 0.3, 1.0)]);
+        let three = residual_of(vec![
+            coord("temp", 0.2, 1.0),
+            coord("toxin", 0.2, 1.0),
+            coord("forage", 0.2, 1.0),
+            coord("humidity", 0.9, 0.0),
+        ]);
+        assert!((two.vt - three.vt).abs() < 1e-12);
+        assert!((risk::residual_per_active_corridor(&two) - 0.3).abs() < 1e-12);
+        assert!((risk::residual_per_active_corridor(&three) - 0.2).abs() < 1e-12);
+        assert_eq!(risk::residual_per_active_corridor(&residual_of(Vec::new())), 0.0);
+    }
+
+    #[test]
+    fn test_protected_hives_respects_radius() {
+        let alpha = sample_envelope();
+        let mut near = sample_envelope();
+        near.hive_id = "hive-near".into();
+        let mut far = sample_envelope();
+        far.hive_id = "hive-far".into();
+
+        let positions: HashMap<String, (f64, f64)> = [
+            ("hive-alpha".to_string(), (0.0, 0.0)),
+            ("hive-near".to_string(), (300.0, 400.0)),
+            ("hive-far".to_string(), (3000.0, 0.0)),
+        ]
+        .into_iter()
+        .collect();
+
+        let protected = protected_hives(&sample_adjustment(), &[alpha, near, far], 1000.0, &positions);
+        assert_eq!(protected, vec!["hive-alpha".to_string(), "hive-near".to_string()]);
+    }
+
+    #[test]
+    fn test_ledger_event_keeps_pre_envelope() {
+        let mut ledger = HiveInnerLedger::new();
+        let mut wildflowers = sample_adjustment();
+        wildflowers.delta_pesticide_exposure_ppb = 0.0;
+        wildflowers.delta_water_availability_index = 0.1;
+        wildflowers.delta_forage_radius_m = 200.0;
+        wildflowers.delta_forage_diversity_index = 0.15;
+        wildflowers.delta_eco_impact_score_corridor = 10.0;
+
+        let post = ledger.apply_adjustment(sample_envelope(), wildflowers).unwrap();
+        let event = &ledger.events()[0];
+        assert_ne!(event.pre_envelope.forage_radius_m, event.post_envelope.forage_radius_m);
+        assert_eq!(event.pre_envelope.forage_radius_m, 1500.0);
+        assert_eq!(event.post_envelope.forage_radius_m, post.forage_radius_m);
+    }
+
+    #[test]
+    fn test_eco_impact_rate_of_improving_history() {
+        let mut ledger = HiveInnerLedger::new();
+        let mut env = sample_envelope();
+        // A sharp early drop falls outside the window; the last 6 hours climb 2 points/h.
+        env.eco_impact_score_corridor = 90.0;
+        push_event(&mut ledger, &env, 0);
+        for h in 10..=16 {
+            env.eco_impact_score_corridor = 40.0 + 2.0 * (h - 10) as f32;
+            push_event(&mut ledger, &env, h);
+        }
+
+        let rate = eco_impact_rate(&ledger, "hive-alpha", chrono::Duration::hours(6));
+        assert!((rate - 2.0).abs() < 1e-4, "rate {}", rate);
+        assert!(eco_impact_rate(&ledger, "hive-alpha", chrono::Duration::hours(24)) < rate);
+        assert_eq!(eco_impact_rate(&ledger, "hive-alpha", chrono::Duration::zero()), 0.0);
+        assert_eq!(eco_impact_rate(&ledger, "hive-beta", chrono::Duration::hours(6)), 0.0);
+    }
+
+    #[test]
+    fn test_band_correlation_of_correlated_histories() {
+        let mut ledger = HiveInnerLedger::new();
+        let mut a = sample_envelope();
+        let mut b = sample_envelope();
+        b.hive_id = "hive-beta".into();
+        for h in 0..6 {
+            a.eco_impact_score_corridor = 40.0 + 5.0 * h as f32;
+            push_event(&mut ledger, &a, h);
+            // hive-beta reports every other hour, tracking the same trend at half scale.
+            if h % 2 == 1 {
+                b.eco_impact_score_corridor = 20.0 + 2.5 * h as f32;
+                push_event(&mut ledger, &b, h);
+            }
+        }
+        let r = band_correlation(&ledger, "hive-alpha", "hive-beta");
+        assert!(r > 0.999, "correlation {}", r);
+    }
+
+    #[test]
+    fn test_compute_residual_checked_rejects_empty_coords() {
+        assert_eq!(
+            risk::compute_residual_checked(&[]),
+            Err(risk::ResidualError::NoCorridors)
+        );
+        assert!(risk::compute_residual_checked(&[coord("temp", 0.5, 2.0)]).is_ok());
+    }
+
+    #[test]
+    fn test_check_adjustment_reports_every_violation() {
+        let mut adj = sample_adjustment();
+        adj.delta_pesticide_exposure_ppb = 2.0;
+        adj.delta_noise_db = 1.0;
+        adj.delta_eco_impact_score_corridor = -1.0;
+
+        let mut ledger = HiveInnerLedger::new();
+        let violations = ledger.check_adjustment(&sample_envelope(), &adj);
+        assert_eq!(violations.len(), 3);
+        assert_eq!(
+            ledger.apply_adjustment(sample_envelope(), adj).unwrap_err(),
+            violations[0]
+        );
+        assert!(ledger.events().is_empty());
+    }
+
+    #[test]
+    fn test_check_adjustment_validates_post_envelope() {
+        let mut env = sample_envelope();
+        env.safe_forage_radius_m_min = 0.0;
+        let mut adj = sample_adjustment();
+        adj.delta_forage_radius_m = -2000.0;
+
+        let mut ledger = HiveInnerLedger::new();
+        let violations = ledger.check_adjustment(&env, &adj);
+        let negative = HiveGuardError::InvalidEnvelope(HiveEnvelopeError::NegativeQuantity {
+            field: "forage_radius_m".into(),
+            value: -500.0,
+        });
+        assert!(violations.contains(&negative), "{:?}", violations);
+        assert!(ledger.apply_adjustment(env, adj).is_err());
+        assert!(ledger.events().is_empty());
+    }
+
+    #[test]
+    fn test_water_availability_lowers_projected_temperature() {
+        let mut dry = sample_adjustment();
+        dry.delta_water_availability_index = 0.0;
+        let mut irrigated = dry.clone();
+        irrigated.delta_water_availability_index = 0.5;
+
+        let env = sample_envelope();
+        assert!(projected_temp_delta(&env, &irrigated) < projected_temp_delta(&env, &dry));
+        assert!((projected_temp_delta(&env, &irrigated) + 1.0).abs() < 1e-6);
+
+        let mut ledger = HiveInnerLedger::new();
+        let post = ledger.apply_adjustment(sample_envelope(), irrigated).unwrap();
+        assert!(post.hive_temperature_c < sample_envelope().hive_temperature_c);
     }
 }
@@ -1,48 +1,359 @@
-use std::io::{self, Write};
+use std::fs;
+use std::io;
+use std::path::Path;
 use std::process;
 
-fn main() {
-    // Constants from biophysical data
-    let r: f64 = 0.3; // growth rate
-    let k: f64 = 150.0; // carrying capacity in millions
-    let alpha: f64 = 0.01; // Varroa impact
-    let beta: f64 = 0.0005; // Pesticide impact
-    let gamma: f64 = 0.2; // Nutrition impact
-    let delta: f64 = 0.05; // Climate impact
-    let epsilon: f64 = 0.4; // Intervention efficacy
+use serde::{Deserialize, Serialize};
 
+/// Logistic growth with stressor and intervention terms (colonies in millions).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelParams {
+    pub r: f64,       // growth rate
+    pub k: f64,       // carrying capacity in millions
+    pub alpha: f64,   // Varroa impact
+    pub beta: f64,    // Pesticide impact
+    pub gamma: f64,   // Nutrition impact
+    pub delta: f64,   // Climate impact
+    pub epsilon: f64, // Intervention efficacy
     // Baseline stressors
-    let v: f64 = 5.0;
-    let p: f64 = 10.0;
-    let n: f64 = 0.15;
-    let c: f64 = 1.5;
-    let i: f64 = 0.8; // Intervention intensity
+    pub v: f64,
+    pub p: f64,
+    pub n: f64,
+    pub c: f64,
+    pub i: f64, // Intervention intensity
+}
+
+impl ModelParams {
+    /// Constants from biophysical data.
+    pub fn baseline() -> Self {
+        Self {
+            r: 0.3,
+            k: 150.0,
+            alpha: 0.01,
+            beta: 0.0005,
+            gamma: 0.2,
+            delta: 0.05,
+            epsilon: 0.4,
+            v: 5.0,
+            p: 10.0,
+            n: 0.15,
+            c: 1.5,
+            i: 0.8,
+        }
+    }
+
+    /// dB/dt for population `b`.
+    pub fn db_dt(&self, b: f64) -> f64 {
+        self.r * b * (1.0 - b / self.k)
+            - self.alpha * self.v * b
+            - self.beta * self.p * b
+            - self.gamma * self.n * b
+            - self.delta * self.c * b
+            + self.epsilon * self.i * b
+    }
+
+    /// Net per-capita growth at low density: r minus stressors plus intervention.
+    fn net_rate(&self) -> f64 {
+        let stress = self.alpha * self.v + self.beta * self.p + self.gamma * self.n + self.delta * self.c;
+        self.r - stress + self.epsilon * self.i
+    }
+
+    /// Analytic equilibrium B* = k * net_rate / r, where dB/dt = 0.
+    pub fn steady_state(&self) -> f64 {
+        self.k * self.net_rate() / self.r
+    }
+
+    /// Upper saturation bound: `SATURATION_FACTOR` times the larger of `k` and the
+    /// steady state, so the clamp never binds below the fixed point.
+    pub fn saturation_cap(&self) -> f64 {
+        SATURATION_FACTOR * self.k.max(self.steady_state())
+    }
+
+    /// Closed-form logistic solution B(t) from `b0`, used to check integrator accuracy.
+    pub fn analytic(&self, b0: f64, t: f64) -> f64 {
+        let b_star = self.steady_state();
+        b_star / (1.0 + (b_star / b0 - 1.0) * (-self.net_rate() * t).exp())
+    }
+}
+
+/// Time-stepping scheme for `simulate`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Integrator {
+    /// Forward Euler: cheap, first-order accurate.
+    #[default]
+    Euler,
+    /// Classical fourth-order Runge-Kutta: four slope evaluations per step.
+    Rk4,
+    /// RK4 with step-doubling error control: each `dt` is covered by substeps that
+    /// halve until the local error is within `ADAPTIVE_TOLERANCE` and grow when it is slack.
+    AdaptiveRk4,
+}
+
+/// Local error bound per substep for `Integrator::AdaptiveRk4`, relative to max(|B|, 1).
+pub const ADAPTIVE_TOLERANCE: f64 = 1e-8;
+/// Smallest substep `Integrator::AdaptiveRk4` will take, as a fraction of `dt`.
+const MIN_SUBSTEP_FRACTION: f64 = 1e-6;
+
+fn rk4_step(params: &ModelParams, b: f64, dt: f64) -> f64 {
+    let k1 = params.db_dt(b);
+    let k2 = params.db_dt(b + 0.5 * dt * k1);
+    let k3 = params.db_dt(b + 0.5 * dt * k2);
+    let k4 = params.db_dt(b + dt * k3);
+    b + dt / 6.0 * (k1 + 2.0 * k2 + 2.0 * k3 + k4)
+}
+
+/// Cover `dt` with RK4 substeps, comparing one step of size h against two of h/2.
+/// The difference estimates the local error (scaled by 1/15 for a fourth-order
+/// method); accepted steps use the Richardson-extrapolated value.
+fn adaptive_rk4(params: &ModelParams, mut b: f64, dt: f64) -> f64 {
+    let min_h = dt * MIN_SUBSTEP_FRACTION;
+    let mut t = 0.0;
+    let mut h = dt;
+    while t < dt {
+        h = h.min(dt - t);
+        let full = rk4_step(params, b, h);
+        let half = rk4_step(params, rk4_step(params, b, 0.5 * h), 0.5 * h);
+        let err = (half - full).abs() / 15.0;
+        let tol = ADAPTIVE_TOLERANCE * b.abs().max(1.0);
+        if err <= tol || h <= min_h {
+            b = half + (half - full) / 15.0;
+            t += h;
+            if err < tol / 32.0 {
+                h *= 2.0;
+            }
+        } else {
+            h *= 0.5;
+        }
+    }
+    b
+}
+
+impl Integrator {
+    /// Raw (unclamped) population after one step of size `dt`.
+    fn step(self, params: &ModelParams, b: f64, dt: f64) -> f64 {
+        match self {
+            Integrator::Euler => b + params.db_dt(b) * dt,
+            Integrator::Rk4 => rk4_step(params, b, dt),
+            Integrator::AdaptiveRk4 => adaptive_rk4(params, b, dt),
+        }
+    }
+}
+
+/// Resumable simulation state: parameters, step size, and the trajectory so far.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub params: ModelParams,
+    pub dt: f64,
+    #[serde(default)]
+    pub integrator: Integrator,
+    /// Number of completed integration steps.
+    pub step: usize,
+    /// Population after each step, starting with the initial population.
+    pub trajectory: Vec<f64>,
+}
+
+impl Checkpoint {
+    pub fn new(params: ModelParams, b0: f64, dt: f64) -> Self {
+        Self { params, dt, integrator: Integrator::default(), step: 0, trajectory: vec![b0] }
+    }
+
+    pub fn with_integrator(mut self, integrator: Integrator) -> Self {
+        self.integrator = integrator;
+        self
+    }
+
+    pub fn population(&self) -> f64 {
+        *self.trajectory.last().expect("trajectory always holds the initial population")
+    }
+
+    pub fn save_checkpoint(&self, path: &Path) -> io::Result<()> {
+        let json = serde_json::to_string(self)?;
+        fs::write(path, json)
+    }
+
+    pub fn resume_from(path: &Path) -> io::Result<Self> {
+        let json = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+}
+
+/// Headroom of the saturation bound over the larger of carrying capacity and steady state.
+pub const SATURATION_FACTOR: f64 = 1.5;
+/// Single-step change (as a fraction of carrying capacity) flagged as numerically unstable.
+pub const INSTABILITY_JUMP_FRACTION: f64 = 0.5;
+
+/// Numerical health of a `simulate` call.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SimulationReport {
+    /// True if any step jumped by more than `INSTABILITY_JUMP_FRACTION * k`.
+    pub unstable: bool,
+    /// Steps whose raw update left [0, `ModelParams::saturation_cap`] and was clamped.
+    pub saturated_steps: usize,
+    /// Set when instability was detected; callers should reduce `dt`.
+    pub warning: Option<String>,
+}
+
+/// Advance the simulation by `steps` steps of the checkpoint's integrator, clamping the population to
+/// [0, `ModelParams::saturation_cap`] each step and flagging large jumps.
+/// Safety invariant: abort if the model predicts extinction (representationally impossible harm).
+pub fn simulate(state: &mut Checkpoint, steps: usize) -> Result<SimulationReport, String> {
+    let mut report = SimulationReport::default();
+    let b_max = state.params.saturation_cap();
+    for _ in 0..steps {
+        let b = state.population();
+        let raw = state.integrator.step(&state.params, b, state.dt);
+        let b_next = if raw.is_finite() { raw.clamp(0.0, b_max) } else { b_max };
+        if b_next != raw {
+            report.saturated_steps += 1;
+        }
+        state.step += 1;
+        state.trajectory.push(b_next);
+
+        let jump = (raw - b).abs();
+        if !raw.is_finite() || jump > INSTABILITY_JUMP_FRACTION * state.params.k {
+            if !report.unstable {
+                report.warning = Some(format!(
+                    "Integration unstable at step {} (jump {:.2} with dt {}); reduce dt.",
+                    state.step, jump, state.dt
+                ));
+            }
+            report.unstable = true;
+        }
+        if b_next <= 0.0 {
+            return Err("Model predicts extinction. Adjust parameters.".to_string());
+        }
+    }
+    Ok(report)
+}
+
+fn main() {
+    let params = ModelParams::baseline();
 
     // Initial population
-    let mut b: f64 = 100.0;
     let dt: f64 = 0.1; // time step in years
     let years: usize = 10;
-    let steps: usize = (years as f64 / dt) as usize;
+    let steps_per_year = (1.0 / dt) as usize;
 
-    println!("Year\tPopulation (millions)");
-    println!("0\t{:.2}", b);
+    let mut state = Checkpoint::new(params, 100.0, dt).with_integrator(Integrator::AdaptiveRk4);
 
-    for step in 1..=steps {
-        let db = r * b * (1.0 - b / k) - alpha * v * b - beta * p * b - gamma * n * b - delta * c * b + epsilon * i * b;
-        b += db * dt;
+    println!("Year\tPopulation (millions)");
+    println!("0\t{:.2}", state.population());
 
-        if step % (1.0 / dt) as usize == 0 {
-            println!("{}\t{:.2}", step / (1.0 / dt) as usize, b);
+    for year in 1..=years {
+        match simulate(&mut state, steps_per_year) {
+            Ok(report) => {
+                if let Some(w) = report.warning {
+                    eprintln!("Warning: {}", w);
+                }
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                process::exit(1);
+            }
         }
+        println!("{}\t{:.2}", year, state.population());
+    }
+
+    // Steady-state calculation
+    println!("\nSteady-state population: {:.2} million colonies", params.steady_state());
+}
 
-        // Safety invariant: Abort if model predicts extinction (representationally impossible harm)
-        if b <= 0.0 {
-            eprintln!("Error: Model predicts extinction. Adjust parameters.");
-            process::exit(1);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resume_from_checkpoint_matches_uninterrupted_run() {
+        let mut uninterrupted = Checkpoint::new(ModelParams::baseline(), 100.0, 0.1);
+        simulate(&mut uninterrupted, 100).unwrap();
+
+        let mut first_half = Checkpoint::new(ModelParams::baseline(), 100.0, 0.1);
+        simulate(&mut first_half, 50).unwrap();
+        let path = std::env::temp_dir().join("bee_population_checkpoint_test.json");
+        first_half.save_checkpoint(&path).unwrap();
+
+        let mut resumed = Checkpoint::resume_from(&path).unwrap();
+        simulate(&mut resumed, 50).unwrap();
+        let _ = fs::remove_file(&path);
+
+        // JSON floats round-trip to within an ulp or so, so compare with a tight tolerance.
+        assert_eq!(resumed.step, 100);
+        assert_eq!(resumed.trajectory.len(), uninterrupted.trajectory.len());
+        for (a, b) in resumed.trajectory.iter().zip(&uninterrupted.trajectory) {
+            assert!((a - b).abs() < 1e-9);
         }
     }
 
-    // Steady-state calculation
-    let steady = k * (1.0 - (alpha * v + beta * p + gamma * n + delta * c) / r + epsilon * i);
-    println!("\nSteady-state population: {:.2} million colonies", steady);
+    #[test]
+    fn test_large_dt_flags_instability_and_saturates() {
+        let params = ModelParams::baseline();
+        // One 10-year Euler step overshoots past the cap; a second would crash to zero.
+        let mut state = Checkpoint::new(params, 100.0, 10.0);
+        let report = simulate(&mut state, 1).unwrap();
+
+        assert!(report.unstable);
+        assert!(report.warning.is_some());
+        assert_eq!(report.saturated_steps, 1);
+        assert!(state.trajectory.iter().all(|b| *b >= 0.0 && *b <= params.saturation_cap()));
+
+        let mut stable = Checkpoint::new(params, 100.0, 0.1);
+        assert!(!simulate(&mut stable, 100).unwrap().unstable);
+    }
+
+    #[test]
+    fn test_rk4_tracks_analytic_solution_with_fewer_steps() {
+        let params = ModelParams { i: 0.3, ..ModelParams::baseline() };
+        let b0 = 100.0;
+
+        let mut euler = Checkpoint::new(params, b0, 0.1);
+        simulate(&mut euler, 100).unwrap();
+        let mut rk4 = Checkpoint::new(params, b0, 1.0).with_integrator(Integrator::Rk4);
+        simulate(&mut rk4, 10).unwrap();
+
+        let exact = params.analytic(b0, 10.0);
+        let euler_err = (euler.population() - exact).abs();
+        let rk4_err = (rk4.population() - exact).abs();
+        assert!(rk4_err < euler_err, "rk4 {} vs euler {}", rk4_err, euler_err);
+
+        // Over a longer horizon RK4 settles onto the analytic steady state while still
+        // tracking the exact trajectory more closely than Euler at the same dt.
+        simulate(&mut rk4, 20).unwrap();
+        let mut euler_coarse = Checkpoint::new(params, b0, 1.0);
+        simulate(&mut euler_coarse, 30).unwrap();
+        let exact = params.analytic(b0, 30.0);
+        assert!((rk4.population() - params.steady_state()).abs() < 0.5);
+        assert!((rk4.population() - exact).abs() < (euler_coarse.population() - exact).abs());
+    }
+
+    #[test]
+    fn test_saturation_cap_sits_above_steady_state() {
+        let params = ModelParams::baseline();
+        assert!(params.steady_state() > params.k);
+        assert!(params.saturation_cap() > params.steady_state());
+
+        let mut state = Checkpoint::new(params, params.steady_state(), 0.1);
+        let report = simulate(&mut state, 50).unwrap();
+        assert_eq!(report.saturated_steps, 0);
+        assert!((state.population() - params.steady_state()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_adaptive_rk4_converges_to_steady_state_with_default_params() {
+        let params = ModelParams::baseline();
+        let b0 = 100.0;
+
+        // Large output steps: the controller subdivides them to stay on the exact curve.
+        let mut adaptive = Checkpoint::new(params, b0, 2.0).with_integrator(Integrator::AdaptiveRk4);
+        let report = simulate(&mut adaptive, 10).unwrap();
+        assert!(!report.unstable);
+        assert_eq!(report.saturated_steps, 0);
+        let mut plain = Checkpoint::new(params, b0, 2.0).with_integrator(Integrator::Rk4);
+        simulate(&mut plain, 10).unwrap();
+        let exact = params.analytic(b0, 20.0);
+        assert!((adaptive.population() - exact).abs() < 1e-6);
+        assert!((adaptive.population() - exact).abs() < (plain.population() - exact).abs());
+
+        simulate(&mut adaptive, 40).unwrap();
+        assert!((adaptive.population() - params.steady_state()).abs() < 1e-6);
+    }
 }